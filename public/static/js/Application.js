@@ -1,14 +1,17 @@
 "use strict";
 
-define([ "ActivityItems", "knockout", "moment", "reqwest" ], function(ActivityItems, ko, moment, reqwest) {
+define([ "ActivityItems", "IssueTimeline", "knockout", "moment", "reqwest" ], function(ActivityItems, IssueTimeline, ko, moment, reqwest) {
 	const HTML_DATE_FORMAT = moment.HTML5_FMT.DATE;
 	const USER_DATE_FORMAT = "D MMMM YYYY";
 
 	const Application = function() {
 		this.selectedDate = ko.observable(moment().format(HTML_DATE_FORMAT));
 		this.activityItems = ko.observable(ActivityItems.empty());
+		this.issueKey = ko.observable("");
+		this.issueTimeline = ko.observable(IssueTimeline.empty());
 		this.errorMessage = ko.observable("");
 		this.isResultVisible = ko.observable(false);
+		this.isTimelineVisible = ko.observable(false);
 		this.isErrorVisible = ko.observable(false);
 
 		this.isDateValid = ko.pureComputed(function() {
@@ -35,6 +38,7 @@ define([ "ActivityItems", "knockout", "moment", "reqwest" ], function(ActivityIt
 		}
 
 		this.isResultVisible(false);
+		this.isTimelineVisible(false);
 		this.isErrorVisible(false);
 
 		const self = this;
@@ -58,6 +62,50 @@ define([ "ActivityItems", "knockout", "moment", "reqwest" ], function(ActivityIt
 		});
 	};
 
+	Application.prototype.showTimeline = function() {
+		const issueKey = this.issueKey().trim().toUpperCase();
+
+		if (issueKey === "") {
+			this.errorMessage("Missing issue key");
+			this.isErrorVisible(true);
+
+			return;
+		}
+
+		this.isResultVisible(false);
+		this.isTimelineVisible(false);
+		this.isErrorVisible(false);
+
+		const self = this;
+
+		reqwest({
+			url: '/api/v1/issue/' + encodeURIComponent(issueKey),
+			type: 'json',
+			method: 'get',
+		}).then(function(resp) {
+			if (resp.success) {
+				self.issueTimeline(IssueTimeline.fromResponse(resp.issue_key, resp.entries));
+				self.isTimelineVisible(true);
+			} else {
+				self.errorMessage(resp.message);
+				self.isErrorVisible(true);
+			}
+		}).fail(function(err, msg) {
+			self.errorMessage(msg);
+			self.isErrorVisible(true);
+		});
+	};
+
+	Application.prototype.getTimelineHeader = function() {
+		const timeline = this.issueTimeline();
+
+		if (timeline.items.length === 0) {
+			return "No activity on " + timeline.issueKey;
+		}
+
+		return "Activity on " + timeline.issueKey;
+	};
+
 	Application.prototype.getResultHeader = function() {
 		if (this.isDateValid) {
 			const selectedDate = this.selectedDate();
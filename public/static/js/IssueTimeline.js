@@ -0,0 +1,30 @@
+"use strict";
+
+define([ "knockout", "moment" ], function(ko, moment) {
+	const USER_TIME_FORMAT = "D MMMM YYYY HH:mm";
+
+	const IssueTimeline = function(issueKey, items) {
+		this.issueKey = issueKey;
+		this.items = items;
+	};
+
+	IssueTimeline.empty = function() {
+		return new IssueTimeline("", []);
+	};
+
+	IssueTimeline.fromResponse = function(issueKey, entries) {
+		const items = entries.map(function(entry) {
+			return {
+				time: moment(entry.published).format(USER_TIME_FORMAT),
+				member: entry.member,
+				title: entry.title,
+				link: entry.link,
+				content: entry.content || "",
+			};
+		});
+
+		return new IssueTimeline(issueKey, items);
+	};
+
+	return IssueTimeline;
+});
@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+pub type BackupResult<T> = Result<T, BackupError>;
+
+#[derive(Debug)]
+pub enum BackupError {
+    IoError { message: String },
+    DatabaseError { message: String },
+}
+
+impl BackupError {
+    pub fn io_error<E>(error: E) -> BackupError
+    where
+        E: Error,
+    {
+        warn!("Backup IO error: {}", error);
+
+        BackupError::IoError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn database_error<E>(error: E) -> BackupError
+    where
+        E: Error,
+    {
+        warn!("Backup database error: {}", error);
+
+        BackupError::DatabaseError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl Display for BackupError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            BackupError::IoError { ref message } => write!(f, "IO error: `{}`", message),
+            BackupError::DatabaseError { ref message } => {
+                write!(f, "Database error: `{}`", message)
+            }
+        }
+    }
+}
+
+impl Error for BackupError {}
@@ -0,0 +1,221 @@
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::thread::Builder;
+use std::thread::JoinHandle;
+use std::time::Duration as StdDuration;
+
+mod error;
+
+pub use self::error::BackupError;
+pub use self::error::BackupResult;
+
+use config::BackupConfig;
+use database::Database;
+
+const DAILY_PREFIX: &str = "daily-";
+const WEEKLY_PREFIX: &str = "weekly-";
+const EXTENSION: &str = ".sqlite";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DAYS_PER_WEEK: i64 = 7;
+const MIN_INTERVAL: u64 = 60;
+
+/// Takes periodic database snapshots into backup directory. Snapshot is named after UTC date,
+/// so repeated backups on same day replace daily snapshot. Weekly snapshot is copied from daily
+/// one if last weekly snapshot is at least week old. Oldest snapshots above configured numbers
+/// are deleted. Entries older than retention age are pruned also when snapshot failed or
+/// snapshots are disabled.
+#[derive(Debug)]
+pub struct BackupWorker {
+    config: BackupConfig,
+    database: Database,
+    snapshots: bool,
+}
+
+/// Running backup thread.
+#[derive(Debug)]
+pub struct BackupHandle {
+    stop: Sender<()>,
+    join: JoinHandle<()>,
+}
+
+impl BackupHandle {
+    /// Asks backup thread to stop, running backup will be finished. Returns thread handle to
+    /// wait for.
+    pub fn stop(self) -> JoinHandle<()> {
+        drop(self.stop);
+
+        self.join
+    }
+}
+
+impl BackupWorker {
+    pub fn new(config: &BackupConfig, database: Database) -> BackupWorker {
+        BackupWorker {
+            config: config.clone(),
+            database,
+            snapshots: true,
+        }
+    }
+
+    /// Disables snapshots for database backends which do not support them, only retention
+    /// policy is applied.
+    pub fn with_snapshots(mut self, snapshots: bool) -> BackupWorker {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Starts thread taking backup immediately and then every configured interval.
+    pub fn start(self) -> BackupHandle {
+        let (stop, receiver) = mpsc::channel();
+        let join = Builder::new()
+            .name("Backup".into())
+            .spawn(move || self.run(&receiver))
+            .expect("Failed to start backup thread");
+
+        BackupHandle { stop, join }
+    }
+
+    fn run(self, stop: &Receiver<()>) {
+        let interval = StdDuration::from_secs(self.config.interval().max(MIN_INTERVAL));
+
+        info!("Backup started");
+
+        loop {
+            if let Err(err) = self.backup(Utc::now()) {
+                warn!("Backup failed: {}", err);
+            }
+
+            match stop.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => {}
+                _ => break,
+            }
+        }
+
+        info!("Backup stopped");
+    }
+
+    /// Takes snapshot for given time, rotates snapshots and applies retention policy. Retention
+    /// policy is applied even if snapshot failed, first error is returned.
+    pub fn backup(&self, now: DateTime<Utc>) -> BackupResult<()> {
+        let snapshot = if self.snapshots {
+            self.snapshot(now)
+        } else {
+            Ok(())
+        };
+        let retention = self.apply_retention(now);
+
+        snapshot.and(retention)
+    }
+
+    fn snapshot(&self, now: DateTime<Utc>) -> BackupResult<()> {
+        let directory = Path::new(self.config.directory());
+        let date = now.format(DATE_FORMAT).to_string();
+        let daily = directory.join(format!("{}{}{}", DAILY_PREFIX, date, EXTENSION));
+        let temporary = directory.join(format!("{}{}{}.tmp", DAILY_PREFIX, date, EXTENSION));
+
+        fs::create_dir_all(directory).map_err(BackupError::io_error)?;
+        remove_file(&temporary)?;
+
+        self.database
+            .backup(&temporary.to_string_lossy())
+            .map_err(BackupError::database_error)?;
+        fs::rename(&temporary, &daily).map_err(BackupError::io_error)?;
+
+        info!("Database snapshot saved to {}", daily.display());
+
+        if self.config.weekly() > 0 && is_weekly_due(directory, now.date_naive())? {
+            let weekly = directory.join(format!("{}{}{}", WEEKLY_PREFIX, date, EXTENSION));
+
+            fs::copy(&daily, &weekly).map_err(BackupError::io_error)?;
+
+            info!("Weekly snapshot saved to {}", weekly.display());
+        }
+
+        rotate(directory, DAILY_PREFIX, self.config.daily())?;
+        rotate(directory, WEEKLY_PREFIX, self.config.weekly())
+    }
+
+    /// Deletes entries older than retention age, except newest entry of every member.
+    fn apply_retention(&self, now: DateTime<Utc>) -> BackupResult<()> {
+        if let Some(days) = self.config.retention_days() {
+            let before = now - Duration::days(days as i64);
+            let deleted = self
+                .database
+                .delete_published_before(before.timestamp())
+                .map_err(BackupError::database_error)?;
+
+            if deleted > 0 {
+                info!("Deleted {} entries older than {} days", deleted, days);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns snapshots with given prefix ordered by date.
+fn snapshots(directory: &Path, prefix: &str) -> BackupResult<Vec<(NaiveDate, PathBuf)>> {
+    let mut result = Vec::new();
+
+    for entry in fs::read_dir(directory).map_err(BackupError::io_error)? {
+        let path = entry.map_err(BackupError::io_error)?.path();
+        let date = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| name.starts_with(prefix) && name.ends_with(EXTENSION))
+            .and_then(|name| {
+                let date = &name[prefix.len()..name.len() - EXTENSION.len()];
+
+                NaiveDate::parse_from_str(date, DATE_FORMAT).ok()
+            });
+
+        if let Some(date) = date {
+            result.push((date, path));
+        }
+    }
+
+    result.sort();
+
+    Ok(result)
+}
+
+fn is_weekly_due(directory: &Path, today: NaiveDate) -> BackupResult<bool> {
+    let weekly = snapshots(directory, WEEKLY_PREFIX)?;
+
+    Ok(match weekly.last() {
+        Some(&(date, _)) => (today - date).num_days() >= DAYS_PER_WEEK,
+        None => true,
+    })
+}
+
+/// Deletes oldest snapshots with given prefix keeping given number of newest ones.
+fn rotate(directory: &Path, prefix: &str, keep: usize) -> BackupResult<()> {
+    let snapshots = snapshots(directory, prefix)?;
+    let n_removed = snapshots.len().saturating_sub(keep);
+
+    for (_, path) in snapshots.into_iter().take(n_removed) {
+        info!("Removing snapshot {}", path.display());
+
+        remove_file(&path)?;
+    }
+
+    Ok(())
+}
+
+fn remove_file(path: &Path) -> BackupResult<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(ref err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(BackupError::io_error(err)),
+    }
+}
@@ -0,0 +1,276 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Write;
+use time;
+
+use database::Database;
+use database::EntryData;
+use database::RevisionData;
+
+use super::CommandError;
+use super::CommandResult;
+
+const ARCHIVE_VERSION: u32 = 1;
+const PAGE_SIZE: usize = 500;
+
+/// Line of archive. Archive starts with header, contains entries and revisions and ends with
+/// footer holding number of written records to detect truncated archives.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ArchiveRecord {
+    Header {
+        version: u32,
+        created: i64,
+    },
+    Entry {
+        id: String,
+        author: String,
+        published: i64,
+        updated: i64,
+        data: String,
+    },
+    Revision {
+        id: String,
+        updated: i64,
+        data: String,
+    },
+    Footer {
+        entries: usize,
+        revisions: usize,
+    },
+}
+
+/// Number of entries and revisions written to or read from archive.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ArchiveStats {
+    entries: usize,
+    revisions: usize,
+}
+
+impl Display for ArchiveStats {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "{} entries and {} revisions",
+            self.entries, self.revisions
+        )
+    }
+}
+
+impl ArchiveStats {
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    pub fn revisions(&self) -> usize {
+        self.revisions
+    }
+}
+
+/// Writes all entries and revisions to gzip compressed JSON Lines archive. Worker position is
+/// derived from stored entries, so it is restored together with them.
+pub fn export(database: &Database, path: &str) -> CommandResult<ArchiveStats> {
+    let file = File::create(path).map_err(CommandError::archive_error)?;
+    let mut writer = BufWriter::new(GzEncoder::new(file, Compression::default()));
+    let mut stats = ArchiveStats::default();
+
+    write_record(
+        &mut writer,
+        &ArchiveRecord::Header {
+            version: ARCHIVE_VERSION,
+            created: time::get_time().sec,
+        },
+    )?;
+
+    let mut last_id = String::new();
+
+    loop {
+        let entries = database
+            .entries_after(&last_id, PAGE_SIZE)
+            .map_err(CommandError::database_error)?;
+
+        for (author, entry) in &entries {
+            write_record(&mut writer, &entry_record(author, entry))?;
+            stats.entries += 1;
+        }
+
+        match entries.last() {
+            Some((_, entry)) => last_id = entry.id().into(),
+            None => break,
+        }
+    }
+
+    let mut last_revision = (String::new(), 0);
+
+    loop {
+        let revisions = database
+            .revisions_after(&last_revision.0, last_revision.1, PAGE_SIZE)
+            .map_err(CommandError::database_error)?;
+
+        for revision in &revisions {
+            write_record(&mut writer, &revision_record(revision))?;
+            stats.revisions += 1;
+        }
+
+        match revisions.last() {
+            Some(revision) => last_revision = (revision.id().into(), revision.updated()),
+            None => break,
+        }
+    }
+
+    write_record(
+        &mut writer,
+        &ArchiveRecord::Footer {
+            entries: stats.entries,
+            revisions: stats.revisions,
+        },
+    )?;
+
+    writer
+        .into_inner()
+        .map_err(|err| CommandError::archive_error(err.into_error()))?
+        .finish()
+        .map_err(CommandError::archive_error)?;
+
+    Ok(stats)
+}
+
+/// Loads archive into empty database. Whole archive is read and checked against its footer
+/// before records are stored in single transaction, so invalid archive leaves database empty.
+/// Numbers of restored records are checked against database content.
+pub fn restore(database: &Database, path: &str) -> CommandResult<ArchiveStats> {
+    let entry_count = database
+        .entry_count()
+        .map_err(CommandError::database_error)?;
+    let revision_count = database
+        .revision_count()
+        .map_err(CommandError::database_error)?;
+
+    if entry_count > 0 || revision_count > 0 {
+        return Err(CommandError::database_not_empty());
+    }
+
+    let file = File::open(path).map_err(CommandError::archive_error)?;
+    let reader = BufReader::new(GzDecoder::new(file));
+    let mut entries = Vec::new();
+    let mut revisions = Vec::new();
+    let mut stats = ArchiveStats::default();
+    let mut header_seen = false;
+    let mut footer = None;
+
+    for line in reader.lines() {
+        let line = line.map_err(CommandError::archive_error)?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if footer.is_some() {
+            return Err(CommandError::invalid_archive("records after footer"));
+        }
+
+        let record: ArchiveRecord =
+            serde_json::from_str(&line).map_err(CommandError::archive_error)?;
+
+        match record {
+            ArchiveRecord::Header { version, .. } if !header_seen => {
+                if version != ARCHIVE_VERSION {
+                    return Err(CommandError::invalid_archive("unsupported version"));
+                }
+
+                header_seen = true;
+            }
+            _ if !header_seen => return Err(CommandError::invalid_archive("missing header")),
+            ArchiveRecord::Header { .. } => {
+                return Err(CommandError::invalid_archive("duplicate header"));
+            }
+            ArchiveRecord::Entry {
+                id,
+                author,
+                published,
+                updated,
+                data,
+            } => {
+                entries.push((author, EntryData::new(&id, published, updated, &data)));
+                stats.entries += 1;
+            }
+            ArchiveRecord::Revision { id, updated, data } => {
+                revisions.push(RevisionData::new(&id, updated, &data));
+                stats.revisions += 1;
+            }
+            ArchiveRecord::Footer { entries, revisions } => {
+                footer = Some(ArchiveStats { entries, revisions })
+            }
+        }
+    }
+
+    match footer {
+        Some(footer) if footer == stats => {}
+        Some(footer) => return Err(CommandError::count_mismatch("archive", footer, stats)),
+        None => {
+            return Err(CommandError::invalid_archive(
+                "missing footer, archive truncated",
+            ))
+        }
+    }
+
+    database
+        .restore(&entries, &revisions)
+        .map_err(CommandError::database_error)?;
+    verify_counts(database, stats)?;
+
+    Ok(stats)
+}
+
+fn entry_record(author: &str, entry: &EntryData) -> ArchiveRecord {
+    ArchiveRecord::Entry {
+        id: entry.id().into(),
+        author: author.into(),
+        published: entry.published(),
+        updated: entry.updated(),
+        data: entry.data().into(),
+    }
+}
+
+fn revision_record(revision: &RevisionData) -> ArchiveRecord {
+    ArchiveRecord::Revision {
+        id: revision.id().into(),
+        updated: revision.updated(),
+        data: revision.data().into(),
+    }
+}
+
+fn write_record<W>(writer: &mut W, record: &ArchiveRecord) -> CommandResult<()>
+where
+    W: Write,
+{
+    serde_json::to_writer(&mut *writer, record).map_err(CommandError::archive_error)?;
+    writer.write_all(b"\n").map_err(CommandError::archive_error)
+}
+
+/// Checks that database contains exactly given number of entries and revisions.
+fn verify_counts(database: &Database, stats: ArchiveStats) -> CommandResult<()> {
+    let actual = ArchiveStats {
+        entries: database
+            .entry_count()
+            .map_err(CommandError::database_error)?,
+        revisions: database
+            .revision_count()
+            .map_err(CommandError::database_error)?,
+    };
+
+    if actual == stats {
+        Ok(())
+    } else {
+        Err(CommandError::count_mismatch("database", stats, actual))
+    }
+}
@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+#[derive(Debug)]
+pub enum CommandError {
+    UnknownCommand {
+        name: String,
+    },
+    MissingArguments {
+        command: String,
+    },
+    InvalidArgument {
+        name: String,
+        value: String,
+    },
+    ReadError {
+        path: String,
+        message: String,
+    },
+    ArchiveError {
+        message: String,
+    },
+    InvalidArchive {
+        message: String,
+    },
+    DatabaseNotEmpty,
+    CountMismatch {
+        source: String,
+        expected: String,
+        actual: String,
+    },
+    DatabaseError {
+        message: String,
+    },
+    SerializationError {
+        message: String,
+    },
+}
+
+impl CommandError {
+    pub fn unknown_command(name: &str) -> CommandError {
+        error!("Unknown command: {}", name);
+
+        CommandError::UnknownCommand { name: name.into() }
+    }
+
+    pub fn missing_arguments(command: &str) -> CommandError {
+        error!("Missing arguments of command: {}", command);
+
+        CommandError::MissingArguments {
+            command: command.into(),
+        }
+    }
+
+    pub fn invalid_argument(name: &str, value: &str) -> CommandError {
+        error!("Invalid value of {}: {}", name, value);
+
+        CommandError::InvalidArgument {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Creates error of file which import skips, logged by caller.
+    pub fn read_error<E>(path: &Path, error: E) -> CommandError
+    where
+        E: Error,
+    {
+        CommandError::ReadError {
+            path: format!("{}", path.display()),
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn archive_error<E>(error: E) -> CommandError
+    where
+        E: Error,
+    {
+        error!("Archive error: {}", error);
+
+        CommandError::ArchiveError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn invalid_archive(message: &str) -> CommandError {
+        error!("Invalid archive: {}", message);
+
+        CommandError::InvalidArchive {
+            message: message.into(),
+        }
+    }
+
+    pub fn database_not_empty() -> CommandError {
+        error!("Database is not empty");
+
+        CommandError::DatabaseNotEmpty
+    }
+
+    pub fn count_mismatch<T>(source: &str, expected: T, actual: T) -> CommandError
+    where
+        T: Display,
+    {
+        error!("Expected {} in {}, found {}", expected, source, actual);
+
+        CommandError::CountMismatch {
+            source: source.into(),
+            expected: format!("{}", expected),
+            actual: format!("{}", actual),
+        }
+    }
+
+    pub fn database_error<E>(error: E) -> CommandError
+    where
+        E: Error,
+    {
+        error!("Database error: {}", error);
+
+        CommandError::DatabaseError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn serialization_error<E>(error: E) -> CommandError
+    where
+        E: Error,
+    {
+        error!("Serialization error: {}", error);
+
+        CommandError::SerializationError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            CommandError::UnknownCommand { ref name } => write!(f, "Unknown command `{}`", name),
+            CommandError::MissingArguments { ref command } => {
+                write!(f, "Missing arguments of command `{}`", command)
+            }
+            CommandError::InvalidArgument {
+                ref name,
+                ref value,
+            } => write!(f, "Invalid value of `{}`: `{}`", name, value),
+            CommandError::ReadError {
+                ref path,
+                ref message,
+            } => write!(f, "Failed to read `{}`: {}", path, message),
+            CommandError::ArchiveError { ref message } => write!(f, "Archive error: `{}`", message),
+            CommandError::InvalidArchive { ref message } => {
+                write!(f, "Invalid archive: {}", message)
+            }
+            CommandError::DatabaseNotEmpty => write!(f, "Database is not empty"),
+            CommandError::CountMismatch {
+                ref source,
+                ref expected,
+                ref actual,
+            } => write!(f, "Expected {} in {}, found {}", expected, source, actual),
+            CommandError::DatabaseError { ref message } => {
+                write!(f, "Database error: `{}`", message)
+            }
+            CommandError::SerializationError { ref message } => {
+                write!(f, "Serialization error: `{}`", message)
+            }
+        }
+    }
+}
+
+impl Error for CommandError {}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::path::PathBuf;
+
+use config::Config;
+use database::Database;
+use database::EntryData;
+use database::SaveStats;
+use entity;
+use entity::Entry;
+use worker::entry_data;
+
+use super::CommandError;
+use super::CommandResult;
+
+const FEED_EXTENSIONS: &[&str] = &["xml", "atom"];
+
+/// Result of import: number of read and failed files, stored entries and entries of authors who
+/// are not team members.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportStats {
+    files: usize,
+    failed_files: usize,
+    entries: usize,
+    skipped: usize,
+    saved: SaveStats,
+}
+
+impl ImportStats {
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    pub fn failed_files(&self) -> usize {
+        self.failed_files
+    }
+
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn saved(&self) -> SaveStats {
+        self.saved
+    }
+}
+
+/// Reads Atom files and stores entries of team members like worker does, entries already stored
+/// are updated only if changed. Files which can not be read are reported and skipped.
+pub fn import(
+    config: &Config,
+    database: &Database,
+    paths: &[String],
+) -> CommandResult<ImportStats> {
+    let mut stats = ImportStats::default();
+
+    for path in feed_files(paths) {
+        info!("Importing {}", path.display());
+
+        let entries = match read_file(&path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("Failed to read {}: {}", path.display(), err);
+
+                stats.failed_files += 1;
+
+                continue;
+            }
+        };
+        let mut authors: HashMap<&str, Vec<EntryData>> = HashMap::new();
+
+        stats.files += 1;
+
+        for entry in &entries {
+            let member = entry
+                .author()
+                .username()
+                .and_then(|username| config.members().iter().find(|m| *m == username));
+
+            match member {
+                Some(member) => {
+                    let data = entry_data(entry).map_err(CommandError::serialization_error)?;
+
+                    authors.entry(member.as_str()).or_default().push(data);
+                }
+                None => {
+                    debug!("Skipping entry {} of unknown member", entry.id());
+
+                    stats.skipped += 1;
+                }
+            }
+        }
+
+        for (member, entries) in authors {
+            stats.entries += entries.len();
+            stats.saved.add(
+                database
+                    .save_entries(member, &entries)
+                    .map_err(CommandError::database_error)?,
+            );
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Returns given files and feed files of given directories in name order.
+fn feed_files(paths: &[String]) -> Vec<PathBuf> {
+    let mut result = Vec::new();
+
+    for path in paths {
+        let path = Path::new(path);
+
+        if !path.is_dir() {
+            result.push(path.to_path_buf());
+
+            continue;
+        }
+
+        let mut files: Vec<PathBuf> = match fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && is_feed_file(path))
+                .collect(),
+            Err(err) => {
+                warn!("Failed to read directory {}: {}", path.display(), err);
+
+                result.push(path.to_path_buf());
+
+                continue;
+            }
+        };
+
+        files.sort();
+        result.extend(files);
+    }
+
+    result
+}
+
+fn is_feed_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| FEED_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn read_file(path: &Path) -> CommandResult<Vec<Entry>> {
+    let file = File::open(path).map_err(|err| CommandError::read_error(path, err))?;
+    let feed =
+        entity::read(BufReader::new(file)).map_err(|err| CommandError::read_error(path, err))?;
+
+    Ok(feed.entries().into())
+}
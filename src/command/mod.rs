@@ -0,0 +1,70 @@
+use std::env::args;
+
+use database::SearchQuery;
+
+mod archive;
+mod error;
+mod import;
+mod search;
+
+pub use self::archive::export;
+pub use self::archive::restore;
+pub use self::error::CommandError;
+pub use self::error::CommandResult;
+pub use self::import::import;
+pub use self::search::search;
+
+/// Command given after configuration path. Without command HTTP server and worker are started.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Serve,
+    /// Stores entries of Atom files, directories are scanned for `.xml` and `.atom` files.
+    Import {
+        paths: Vec<String>,
+    },
+    /// Writes all entries to gzip compressed JSON Lines archive.
+    Export {
+        path: String,
+    },
+    /// Loads entries from archive into empty database.
+    Restore {
+        path: String,
+    },
+    /// Prints entries matching full-text query.
+    Search {
+        query: SearchQuery,
+    },
+}
+
+impl Command {
+    /// Returns command from application arguments following configuration path.
+    pub fn from_args() -> CommandResult<Command> {
+        let args: Vec<String> = args().skip(2).collect();
+
+        Command::parse(&args)
+    }
+
+    fn parse(args: &[String]) -> CommandResult<Command> {
+        let name = match args.first() {
+            Some(name) => name.as_str(),
+            None => return Ok(Command::Serve),
+        };
+
+        match name {
+            "import" if args.len() > 1 => Ok(Command::Import {
+                paths: args[1..].into(),
+            }),
+            "export" if args.len() == 2 => Ok(Command::Export {
+                path: args[1].clone(),
+            }),
+            "restore" if args.len() == 2 => Ok(Command::Restore {
+                path: args[1].clone(),
+            }),
+            "search" => Ok(Command::Search {
+                query: search::parse_query(&args[1..])?,
+            }),
+            "import" | "export" | "restore" => Err(CommandError::missing_arguments(name)),
+            _ => Err(CommandError::unknown_command(name)),
+        }
+    }
+}
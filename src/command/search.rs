@@ -0,0 +1,116 @@
+use chrono::NaiveDate;
+use chrono::TimeZone;
+use chrono::Utc;
+use serde_yaml;
+
+use database::Database;
+use database::SearchQuery;
+use entity::Entry;
+
+use super::CommandError;
+use super::CommandResult;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_LIMIT: usize = 20;
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Entry found by search with its author and matched text.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    author: String,
+    entry: Entry,
+    snippet: String,
+}
+
+impl SearchMatch {
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn entry(&self) -> &Entry {
+        &self.entry
+    }
+
+    /// Returns part of matched text with matched words enclosed in `**`.
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+}
+
+/// Returns entries matching query, best matches first.
+pub fn search(database: &Database, query: &SearchQuery) -> CommandResult<Vec<SearchMatch>> {
+    let results = database
+        .search(query)
+        .map_err(CommandError::database_error)?;
+
+    results
+        .iter()
+        .map(|result| {
+            Ok(SearchMatch {
+                author: result.author().into(),
+                entry: serde_yaml::from_str(result.data())
+                    .map_err(CommandError::serialization_error)?,
+                snippet: result.snippet().into(),
+            })
+        })
+        .collect()
+}
+
+/// Parses arguments of search command: query words followed by optional filters `--member`
+/// (can be repeated), `--from` and `--to` (inclusive UTC dates), `--application`, `--type` and
+/// `--limit`.
+pub fn parse_query(args: &[String]) -> CommandResult<SearchQuery> {
+    let mut words = Vec::new();
+    let mut members = Vec::new();
+    let mut start_date = None;
+    let mut end_date = None;
+    let mut application = None;
+    let mut object_type = None;
+    let mut limit = DEFAULT_LIMIT;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            words.push(arg.as_str());
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| CommandError::missing_arguments(arg))?;
+
+        match arg.as_str() {
+            "--member" => members.push(value.clone()),
+            "--from" => start_date = Some(parse_date(arg, value)?),
+            "--to" => end_date = Some(parse_date(arg, value)? + SECONDS_PER_DAY - 1),
+            "--application" => application = Some(value.clone()),
+            "--type" => object_type = Some(value.clone()),
+            "--limit" => {
+                limit = value
+                    .parse()
+                    .map_err(|_| CommandError::invalid_argument(arg, value))?
+            }
+            _ => return Err(CommandError::invalid_argument(arg, value)),
+        }
+    }
+
+    if words.is_empty() {
+        return Err(CommandError::missing_arguments("search"));
+    }
+
+    Ok(SearchQuery::new(&words.join(" "), limit)
+        .with_members(&members)
+        .with_interval(start_date, end_date)
+        .with_application(application.as_ref())
+        .with_object_type(object_type.as_ref()))
+}
+
+/// Returns UNIX time of start of given day in UTC.
+fn parse_date(name: &str, value: &str) -> CommandResult<i64> {
+    let start = NaiveDate::parse_from_str(value, DATE_FORMAT)
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .ok_or_else(|| CommandError::invalid_argument(name, value))?;
+
+    Ok(Utc.from_utc_datetime(&start).timestamp())
+}
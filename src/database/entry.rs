@@ -0,0 +1,95 @@
+/// Serialized entry ready to be stored in database.
+#[derive(Debug, Clone)]
+pub struct EntryData {
+    id: String,
+    published: i64,
+    updated: i64,
+    data: String,
+}
+
+impl EntryData {
+    pub fn new(id: &str, published: i64, updated: i64, data: &str) -> EntryData {
+        EntryData {
+            id: id.into(),
+            published,
+            updated,
+            data: data.into(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn published(&self) -> i64 {
+        self.published
+    }
+
+    pub fn updated(&self) -> i64 {
+        self.updated
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+}
+
+/// Previous version of entry moved to revision history.
+#[derive(Debug, Clone)]
+pub struct RevisionData {
+    id: String,
+    updated: i64,
+    data: String,
+}
+
+impl RevisionData {
+    pub fn new(id: &str, updated: i64, data: &str) -> RevisionData {
+        RevisionData {
+            id: id.into(),
+            updated,
+            data: data.into(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn updated(&self) -> i64 {
+        self.updated
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+}
+
+/// Number of inserted and updated entries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SaveStats {
+    inserted: usize,
+    updated: usize,
+}
+
+impl SaveStats {
+    pub fn add_inserted(&mut self) {
+        self.inserted += 1;
+    }
+
+    pub fn add_updated(&mut self) {
+        self.updated += 1;
+    }
+
+    pub fn add(&mut self, stats: SaveStats) {
+        self.inserted += stats.inserted;
+        self.updated += stats.updated;
+    }
+
+    pub fn inserted(&self) -> usize {
+        self.inserted
+    }
+
+    pub fn updated(&self) -> usize {
+        self.updated
+    }
+}
@@ -7,14 +7,17 @@ pub type DatabaseResult<T> = Result<T, DatabaseError>;
 
 #[derive(Debug)]
 pub enum DatabaseError {
-    MutexLockError,
     ConnectionError { message: String },
     ExecutionError { message: String },
     PrepareFailed { message: String },
     BindFailed { message: String },
     NextFailed { message: String },
+    BackupFailed { message: String },
     NoSuchColumn,
     NoSuchValue,
+    MissingSetting { name: String },
+    Unsupported { operation: String },
+    Interrupted,
 }
 
 macro_rules! error_method {
@@ -33,21 +36,20 @@ macro_rules! error_method {
 }
 
 impl DatabaseError {
-    pub fn mutex_lock_error<E>(error: E) -> DatabaseError
-    where
-        E: Error,
-    {
-        warn!("Mutex lock error: {}", error);
-
-        DatabaseError::MutexLockError
-    }
-
     error_method!(connection_error, ConnectionError, "Connection error");
     error_method!(execution_error, ExecutionError, "Execution error");
     error_method!(prepare_failed, PrepareFailed, "Prepare failed");
     error_method!(bind_failed, BindFailed, "Bind failed");
     error_method!(next_failed, NextFailed, "Next failed");
 
+    pub fn backup_failed(message: &str) -> DatabaseError {
+        warn!("Backup failed: {}", message);
+
+        DatabaseError::BackupFailed {
+            message: message.into(),
+        }
+    }
+
     pub fn no_such_column() -> DatabaseError {
         DatabaseError::NoSuchColumn
     }
@@ -55,12 +57,32 @@ impl DatabaseError {
     pub fn no_such_value() -> DatabaseError {
         DatabaseError::NoSuchValue
     }
+
+    pub fn missing_setting(name: &str) -> DatabaseError {
+        error!("Missing database setting `{}`", name);
+
+        DatabaseError::MissingSetting { name: name.into() }
+    }
+
+    pub fn unsupported(operation: &str) -> DatabaseError {
+        warn!(
+            "Operation `{}` is not supported by database backend",
+            operation
+        );
+
+        DatabaseError::Unsupported {
+            operation: operation.into(),
+        }
+    }
+
+    pub fn interrupted() -> DatabaseError {
+        DatabaseError::Interrupted
+    }
 }
 
 impl Display for DatabaseError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self {
-            DatabaseError::MutexLockError => write!(f, "Mutex lock error"),
             DatabaseError::ConnectionError { ref message } => {
                 write!(f, "Connection error: {}", message)
             }
@@ -72,8 +94,18 @@ impl Display for DatabaseError {
             }
             DatabaseError::BindFailed { ref message } => write!(f, "Bind failed: {}", message),
             DatabaseError::NextFailed { ref message } => write!(f, "Next failed: {}", message),
+            DatabaseError::BackupFailed { ref message } => {
+                write!(f, "Backup failed: {}", message)
+            }
             DatabaseError::NoSuchColumn => write!(f, "No such column"),
             DatabaseError::NoSuchValue => write!(f, "No such value"),
+            DatabaseError::MissingSetting { ref name } => {
+                write!(f, "Missing setting: `{}`", name)
+            }
+            DatabaseError::Unsupported { ref operation } => {
+                write!(f, "Unsupported operation: `{}`", operation)
+            }
+            DatabaseError::Interrupted => write!(f, "Interrupted"),
         }
     }
 }
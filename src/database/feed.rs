@@ -0,0 +1,59 @@
+/// Page of entries of team published in given interval, newest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedQuery {
+    members: Vec<String>,
+    start_date: i64,
+    end_date: i64,
+    application: Option<String>,
+    offset: usize,
+    limit: usize,
+}
+
+impl FeedQuery {
+    /// Selects up to `limit` entries published in given UNIX time interval after skipping
+    /// `offset` newer entries.
+    pub fn new(start_date: i64, end_date: i64, offset: usize, limit: usize) -> FeedQuery {
+        FeedQuery {
+            members: Vec::with_capacity(0),
+            start_date,
+            end_date,
+            application: None,
+            offset,
+            limit,
+        }
+    }
+
+    pub fn with_members(mut self, members: &[String]) -> FeedQuery {
+        self.members = members.into();
+        self
+    }
+
+    pub fn with_application(mut self, application: Option<&String>) -> FeedQuery {
+        self.application = application.cloned();
+        self
+    }
+
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    pub fn start_date(&self) -> i64 {
+        self.start_date
+    }
+
+    pub fn end_date(&self) -> i64 {
+        self.end_date
+    }
+
+    pub fn application(&self) -> Option<&String> {
+        self.application.as_ref()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
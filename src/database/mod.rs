@@ -1,64 +1,74 @@
-use sqlite;
-use std::path::Path;
 use std::sync::Arc;
-use std::sync::Mutex;
 
+mod entry;
 mod error;
-mod inner;
+mod feed;
+mod pool;
+mod postgres_storage;
+mod search;
+mod sqlite_storage;
+mod storage;
 
+pub use self::entry::EntryData;
+pub use self::entry::RevisionData;
+pub use self::entry::SaveStats;
 pub use self::error::DatabaseError;
 pub use self::error::DatabaseResult;
+pub use self::feed::FeedQuery;
+pub use self::search::SearchQuery;
+pub use self::search::SearchResult;
+pub use self::storage::Storage;
 
-use self::inner::DatabaseInner;
+use self::postgres_storage::PostgresStorage;
+use self::sqlite_storage::SqliteStorage;
+use config::DatabaseBackend;
 use Config;
 
 #[derive(Debug, Clone)]
 pub struct Database {
-    inner: Arc<Mutex<DatabaseInner>>,
+    storage: Arc<dyn Storage>,
 }
 
 impl Database {
+    /// Connects to storage selected by database backend setting.
     pub fn new(config: &Config) -> DatabaseResult<Database> {
-        let path = config.database().path();
-        let path: &Path = path.as_ref();
-        let initialize = !path.exists();
-        let connection = sqlite::open(path).map_err(DatabaseError::connection_error)?;
-        let mut inner = DatabaseInner::new(connection);
-
-        if initialize {
-            inner.create_database()?;
-        }
-
-        Ok(Database {
-            inner: Arc::new(Mutex::new(inner)),
-        })
+        let config = config.database();
+        let storage: Arc<dyn Storage> = match config.backend() {
+            DatabaseBackend::Sqlite => {
+                let path = config
+                    .path()
+                    .ok_or_else(|| DatabaseError::missing_setting("database.path"))?;
+
+                Arc::new(SqliteStorage::open(path, config.pool_size())?)
+            }
+            DatabaseBackend::Postgres => {
+                let url = config
+                    .url()
+                    .ok_or_else(|| DatabaseError::missing_setting("database.url"))?;
+
+                Arc::new(PostgresStorage::connect(url, config.pool_size())?)
+            }
+        };
+
+        Ok(Database { storage })
     }
 
-    pub fn has_entry(&self, id: &str) -> DatabaseResult<bool> {
-        self.inner
-            .lock()
-            .map_err(DatabaseError::mutex_lock_error)?
-            .has_entry(id)
+    pub fn save_entries(&self, author: &str, entries: &[EntryData]) -> DatabaseResult<SaveStats> {
+        self.storage
+            .save_entries(author, &mut entries.iter().cloned().map(Ok))
     }
 
-    pub fn save_entry(
+    /// Saves entries as they are read, nothing is saved if iterator returns error.
+    pub fn save_entry_stream(
         &self,
-        id: &str,
         author: &str,
-        published: i64,
-        data: &str,
-    ) -> DatabaseResult<()> {
-        self.inner
-            .lock()
-            .map_err(DatabaseError::mutex_lock_error)?
-            .save_entry(id, author, published, data)
+        entries: &mut dyn Iterator<Item = DatabaseResult<EntryData>>,
+    ) -> DatabaseResult<SaveStats> {
+        self.storage.save_entries(author, entries)
     }
 
     pub fn last_published(&self, author: &str) -> DatabaseResult<Option<i64>> {
-        self.inner
-            .lock()
-            .map_err(DatabaseError::mutex_lock_error)?
-            .last_published(author)
+        self.storage.last_published(author)
     }
 
     pub fn published_between(
@@ -67,9 +77,68 @@ impl Database {
         start_date: i64,
         end_date: i64,
     ) -> DatabaseResult<Vec<String>> {
-        self.inner
-            .lock()
-            .map_err(DatabaseError::mutex_lock_error)?
-            .published_between(author, start_date, end_date)
+        self.storage.published_between(author, start_date, end_date)
+    }
+
+    pub fn feed_entries(&self, query: &FeedQuery) -> DatabaseResult<Vec<(String, String)>> {
+        self.storage.feed_entries(query)
+    }
+
+    pub fn entry_count(&self) -> DatabaseResult<usize> {
+        self.storage.entry_count()
+    }
+
+    pub fn revision_count(&self) -> DatabaseResult<usize> {
+        self.storage.revision_count()
+    }
+
+    /// Returns author and entry of up to `limit` entries following given identifier, used to
+    /// read all entries page by page starting from empty identifier.
+    pub fn entries_after(
+        &self,
+        last_id: &str,
+        limit: usize,
+    ) -> DatabaseResult<Vec<(String, EntryData)>> {
+        self.storage.entries_after(last_id, limit)
+    }
+
+    /// Returns up to `limit` revisions following given identifier and update time.
+    pub fn revisions_after(
+        &self,
+        last_id: &str,
+        last_updated: i64,
+        limit: usize,
+    ) -> DatabaseResult<Vec<RevisionData>> {
+        self.storage.revisions_after(last_id, last_updated, limit)
+    }
+
+    /// Stores exported entries and revisions without changes, nothing is stored on error.
+    pub fn restore(
+        &self,
+        entries: &[(String, EntryData)],
+        revisions: &[RevisionData],
+    ) -> DatabaseResult<()> {
+        self.storage.restore(entries, revisions)
+    }
+
+    /// Writes consistent snapshot of database to given path, file must not exist.
+    pub fn backup(&self, path: &str) -> DatabaseResult<()> {
+        self.storage.backup(path)
+    }
+
+    /// Returns author and data of all entries referring to given issue, oldest first.
+    pub fn issue_entries(&self, issue_key: &str) -> DatabaseResult<Vec<(String, String)>> {
+        self.storage.issue_entries(issue_key)
+    }
+
+    /// Returns entries matching full-text query, best matches first.
+    pub fn search(&self, query: &SearchQuery) -> DatabaseResult<Vec<SearchResult>> {
+        self.storage.search(query)
+    }
+
+    /// Deletes entries published before given UNIX time and their revisions, keeps newest entry of
+    /// every member so updates continue from it.
+    pub fn delete_published_before(&self, time: i64) -> DatabaseResult<usize> {
+        self.storage.delete_published_before(time)
     }
 }
@@ -0,0 +1,83 @@
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+/// Fixed set of connections shared between threads. Thread waits until connection is available,
+/// connection is returned to pool when dropped.
+pub struct Pool<C> {
+    connections: Mutex<Vec<C>>,
+    available: Condvar,
+}
+
+/// Connection taken from pool.
+pub struct PooledConnection<'a, C: 'a> {
+    pool: &'a Pool<C>,
+    connection: Option<C>,
+}
+
+impl<C> Pool<C> {
+    pub fn new(connections: Vec<C>) -> Pool<C> {
+        Pool {
+            connections: Mutex::new(connections),
+            available: Condvar::new(),
+        }
+    }
+
+    pub fn get(&self) -> PooledConnection<'_, C> {
+        let mut connections = lock(&self.connections);
+
+        loop {
+            if let Some(connection) = connections.pop() {
+                return PooledConnection {
+                    pool: self,
+                    connection: Some(connection),
+                };
+            }
+
+            connections = self
+                .available
+                .wait(connections)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    fn put(&self, connection: C) {
+        lock(&self.connections).push(connection);
+        self.available.notify_one();
+    }
+}
+
+impl<'a, C> Deref for PooledConnection<'a, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.connection
+            .as_ref()
+            .expect("Connection returned to pool")
+    }
+}
+
+impl<'a, C> DerefMut for PooledConnection<'a, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.connection
+            .as_mut()
+            .expect("Connection returned to pool")
+    }
+}
+
+impl<'a, C> Drop for PooledConnection<'a, C> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.put(connection);
+        }
+    }
+}
+
+/// Locks mutex even if other thread panicked while holding it, so panic of one thread does not
+/// make database unusable for others.
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,22 @@
+CREATE TABLE entry (
+    id TEXT PRIMARY KEY,
+    author TEXT NOT NULL,
+    published BIGINT NOT NULL,
+    updated BIGINT NOT NULL,
+    data TEXT NOT NULL
+) ;
+
+CREATE INDEX fk_entry_author_published ON entry(author, published) ;
+
+CREATE INDEX entry_published ON entry(published) ;
+
+CREATE TABLE entry_revision (
+    id TEXT NOT NULL,
+    updated BIGINT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (id, updated)
+) ;
+
+CREATE TABLE schema_version (
+    version INTEGER NOT NULL
+) ;
@@ -0,0 +1,15 @@
+CREATE TABLE entry_document (
+    id TEXT PRIMARY KEY,
+    application TEXT NOT NULL,
+    object_types TEXT NOT NULL,
+    title TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    content TEXT NOT NULL,
+    document TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', title), 'A') ||
+        setweight(to_tsvector('english', summary), 'B') ||
+        setweight(to_tsvector('english', content), 'C')
+    ) STORED
+) ;
+
+CREATE INDEX entry_document_search ON entry_document USING GIN (document) ;
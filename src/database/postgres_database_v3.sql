@@ -0,0 +1,7 @@
+CREATE TABLE entry_issue (
+    issue_key TEXT NOT NULL,
+    id TEXT NOT NULL,
+    PRIMARY KEY ( issue_key, id )
+) ;
+
+CREATE INDEX entry_issue_id ON entry_issue ( id ) ;
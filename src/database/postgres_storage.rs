@@ -0,0 +1,606 @@
+use native_tls::TlsConnector;
+use postgres::types::FromSql;
+use postgres::types::ToSql;
+use postgres::Client;
+use postgres::GenericClient;
+use postgres::Row;
+use postgres::Transaction;
+use postgres_native_tls::MakeTlsConnector;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use super::pool::Pool;
+use super::pool::PooledConnection;
+use super::search::SearchDocument;
+use super::search::HIGHLIGHT;
+use super::search::SNIPPET_WORDS;
+use super::DatabaseError;
+use super::DatabaseResult;
+use super::EntryData;
+use super::FeedQuery;
+use super::RevisionData;
+use super::SaveStats;
+use super::SearchQuery;
+use super::SearchResult;
+use super::Storage;
+
+/// Schema updates, every item contains database version and script to reach it.
+const UPDATES: &[(i32, &str)] = &[
+    (1, include_str!("postgres_database_v1.sql")),
+    (2, include_str!("postgres_database_v2.sql")),
+    (3, include_str!("postgres_database_v3.sql")),
+];
+/// Latest version adding index of entry data, entries stored before are indexed after update.
+const INDEX_VERSION: i32 = 3;
+/// Number of entries indexed at once.
+const INDEX_PAGE_SIZE: i64 = 500;
+/// Entries published before given time except newest entry of every member, which is kept as
+/// position of next update.
+const EXPIRED_ENTRIES: &str = "SELECT id FROM entry WHERE published < $1 AND published < \
+     ( SELECT MAX(published) FROM entry AS newest WHERE newest.author = entry.author )";
+
+/// Storage in PostgreSQL database. TLS is used as requested by `sslmode` parameter of connection
+/// URL, by default it is used if server supports it.
+pub struct PostgresStorage {
+    url: String,
+    connector: TlsConnector,
+    pool: Pool<Client>,
+}
+
+impl PostgresStorage {
+    /// Opens given number of connections to database given by URL, schema is created or updated
+    /// if needed.
+    pub fn connect(url: &str, connections: usize) -> DatabaseResult<PostgresStorage> {
+        let connector = TlsConnector::new().map_err(DatabaseError::connection_error)?;
+        let mut clients = Vec::with_capacity(connections);
+
+        for _ in 0..connections.max(1) {
+            clients.push(open(url, &connector)?);
+        }
+
+        update_database(&mut clients[0])?;
+
+        Ok(PostgresStorage {
+            url: url.into(),
+            connector,
+            pool: Pool::new(clients),
+        })
+    }
+
+    /// Takes client from pool, client closed e.g. after server restart is replaced with new
+    /// connection.
+    fn client(&self) -> DatabaseResult<PooledConnection<'_, Client>> {
+        let mut client = self.pool.get();
+
+        if client.is_closed() {
+            warn!("Database connection was closed, reconnecting");
+
+            *client = open(&self.url, &self.connector)?;
+        }
+
+        Ok(client)
+    }
+
+    fn count(&self, table: &str) -> DatabaseResult<usize> {
+        let row = self
+            .client()?
+            .query_one(format!("SELECT COUNT(*) FROM {}", table).as_str(), &[])
+            .map_err(DatabaseError::next_failed)?;
+
+        Ok(value::<i64>(&row, 0)? as usize)
+    }
+}
+
+impl Storage for PostgresStorage {
+    fn save_entries(
+        &self,
+        author: &str,
+        entries: &mut dyn Iterator<Item = DatabaseResult<EntryData>>,
+    ) -> DatabaseResult<SaveStats> {
+        let mut client = self.client()?;
+        let mut transaction = transaction(&mut client)?;
+        let mut stats = SaveStats::default();
+
+        for entry in entries {
+            let entry = entry?;
+            let row = transaction
+                .query_opt("SELECT updated FROM entry WHERE id = $1", &[&entry.id()])
+                .map_err(DatabaseError::next_failed)?;
+            let updated = match row {
+                Some(row) => Some(value::<i64>(&row, 0)?),
+                None => None,
+            };
+
+            match updated {
+                None => {
+                    transaction
+                        .execute(
+                            "INSERT INTO entry ( id, author, published, updated, data ) VALUES ( $1, $2, $3, $4, $5 ) ON CONFLICT DO NOTHING",
+                            &[&entry.id(), &author, &entry.published(), &entry.updated(), &entry.data()],
+                        )
+                        .map_err(DatabaseError::execution_error)?;
+                    index_entry(&mut transaction, entry.id(), entry.data())?;
+                    stats.add_inserted();
+                }
+                Some(updated) if updated < entry.updated() => {
+                    transaction
+                        .execute(
+                            "INSERT INTO entry_revision ( id, updated, data ) SELECT id, updated, data FROM entry WHERE id = $1 ON CONFLICT DO NOTHING",
+                            &[&entry.id()],
+                        )
+                        .map_err(DatabaseError::execution_error)?;
+                    transaction
+                        .execute(
+                            "UPDATE entry SET updated = $1, data = $2 WHERE id = $3",
+                            &[&entry.updated(), &entry.data(), &entry.id()],
+                        )
+                        .map_err(DatabaseError::execution_error)?;
+                    index_entry(&mut transaction, entry.id(), entry.data())?;
+                    stats.add_updated();
+                }
+                Some(_) => {}
+            }
+        }
+
+        transaction
+            .commit()
+            .map_err(DatabaseError::execution_error)?;
+
+        Ok(stats)
+    }
+
+    fn last_published(&self, author: &str) -> DatabaseResult<Option<i64>> {
+        let row = self
+            .client()?
+            .query_one(
+                "SELECT MAX(published) FROM entry WHERE author = $1",
+                &[&author],
+            )
+            .map_err(DatabaseError::next_failed)?;
+
+        value(&row, 0)
+    }
+
+    fn published_between(
+        &self,
+        author: &str,
+        start_date: i64,
+        end_date: i64,
+    ) -> DatabaseResult<Vec<String>> {
+        let rows = self
+            .client()?
+            .query(
+                "SELECT data FROM entry WHERE author = $1 AND published BETWEEN $2 AND $3",
+                &[&author, &start_date, &end_date],
+            )
+            .map_err(DatabaseError::next_failed)?;
+
+        rows.iter().map(|row| value(row, 0)).collect()
+    }
+
+    fn feed_entries(&self, query: &FeedQuery) -> DatabaseResult<Vec<(String, String)>> {
+        let mut sql = String::from("SELECT entry.author, entry.data FROM entry");
+
+        if query.application().is_some() {
+            sql.push_str(" JOIN entry_document ON entry_document.id = entry.id");
+        }
+
+        sql.push_str(" WHERE entry.published BETWEEN $1 AND $2");
+
+        let mut params: Vec<Box<dyn ToSql + Sync>> =
+            vec![Box::new(query.start_date()), Box::new(query.end_date())];
+
+        if !query.members().is_empty() {
+            params.push(Box::new(query.members().to_vec()));
+            sql.push_str(&format!(" AND entry.author = ANY( ${} )", params.len()));
+        }
+
+        if let Some(application) = query.application() {
+            params.push(Box::new(application.clone()));
+            sql.push_str(&format!(
+                " AND entry_document.application = ${}",
+                params.len()
+            ));
+        }
+
+        params.push(Box::new(query.limit() as i64));
+        params.push(Box::new(query.offset() as i64));
+        sql.push_str(&format!(
+            " ORDER BY entry.published DESC, entry.id LIMIT ${} OFFSET ${}",
+            params.len() - 1,
+            params.len()
+        ));
+
+        let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param.as_ref()).collect();
+        let rows = self
+            .client()?
+            .query(sql.as_str(), &params)
+            .map_err(DatabaseError::next_failed)?;
+
+        rows.iter()
+            .map(|row| Ok((value(row, 0)?, value(row, 1)?)))
+            .collect()
+    }
+
+    fn entry_count(&self) -> DatabaseResult<usize> {
+        self.count("entry")
+    }
+
+    fn revision_count(&self) -> DatabaseResult<usize> {
+        self.count("entry_revision")
+    }
+
+    fn entries_after(
+        &self,
+        last_id: &str,
+        limit: usize,
+    ) -> DatabaseResult<Vec<(String, EntryData)>> {
+        let rows = self
+            .client()?
+            .query(
+                "SELECT id, author, published, updated, data FROM entry WHERE id > $1 ORDER BY id LIMIT $2",
+                &[&last_id, &(limit as i64)],
+            )
+            .map_err(DatabaseError::next_failed)?;
+        let mut result = Vec::new();
+
+        for row in &rows {
+            let entry = EntryData::new(
+                value(row, 0)?,
+                value(row, 2)?,
+                value(row, 3)?,
+                value(row, 4)?,
+            );
+
+            result.push((value(row, 1)?, entry));
+        }
+
+        Ok(result)
+    }
+
+    fn revisions_after(
+        &self,
+        last_id: &str,
+        last_updated: i64,
+        limit: usize,
+    ) -> DatabaseResult<Vec<RevisionData>> {
+        let rows = self
+            .client()?
+            .query(
+                "SELECT id, updated, data FROM entry_revision WHERE id > $1 OR ( id = $1 AND updated > $2 ) ORDER BY id, updated LIMIT $3",
+                &[&last_id, &last_updated, &(limit as i64)],
+            )
+            .map_err(DatabaseError::next_failed)?;
+        let mut result = Vec::new();
+
+        for row in &rows {
+            result.push(RevisionData::new(
+                value(row, 0)?,
+                value(row, 1)?,
+                value(row, 2)?,
+            ));
+        }
+
+        Ok(result)
+    }
+
+    fn restore(
+        &self,
+        entries: &[(String, EntryData)],
+        revisions: &[RevisionData],
+    ) -> DatabaseResult<()> {
+        let mut client = self.client()?;
+        let mut transaction = transaction(&mut client)?;
+        let entry_statement = transaction
+            .prepare("INSERT INTO entry ( id, author, published, updated, data ) VALUES ( $1, $2, $3, $4, $5 )")
+            .map_err(DatabaseError::prepare_failed)?;
+        let revision_statement = transaction
+            .prepare("INSERT INTO entry_revision ( id, updated, data ) VALUES ( $1, $2, $3 )")
+            .map_err(DatabaseError::prepare_failed)?;
+
+        for (author, entry) in entries {
+            transaction
+                .execute(
+                    &entry_statement,
+                    &[
+                        &entry.id(),
+                        author,
+                        &entry.published(),
+                        &entry.updated(),
+                        &entry.data(),
+                    ],
+                )
+                .map_err(DatabaseError::execution_error)?;
+            index_entry(&mut transaction, entry.id(), entry.data())?;
+        }
+
+        for revision in revisions {
+            transaction
+                .execute(
+                    &revision_statement,
+                    &[&revision.id(), &revision.updated(), &revision.data()],
+                )
+                .map_err(DatabaseError::execution_error)?;
+        }
+
+        transaction.commit().map_err(DatabaseError::execution_error)
+    }
+
+    fn issue_entries(&self, issue_key: &str) -> DatabaseResult<Vec<(String, String)>> {
+        let rows = self
+            .client()?
+            .query(
+                "SELECT entry.author, entry.data FROM entry_issue JOIN entry ON entry.id = entry_issue.id WHERE entry_issue.issue_key = $1 ORDER BY entry.published, entry.id",
+                &[&issue_key],
+            )
+            .map_err(DatabaseError::next_failed)?;
+
+        rows.iter()
+            .map(|row| Ok((value(row, 0)?, value(row, 1)?)))
+            .collect()
+    }
+
+    fn search(&self, query: &SearchQuery) -> DatabaseResult<Vec<SearchResult>> {
+        if query.words().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let options = format!(
+            "StartSel=\"{0}\", StopSel=\"{0}\", MaxWords={1}, MinWords={2}, MaxFragments=1",
+            HIGHLIGHT,
+            SNIPPET_WORDS,
+            SNIPPET_WORDS / 2
+        );
+        let mut sql = String::from(
+            "SELECT entry.author, entry.data, ts_headline('english', entry_document.title || ' ' || entry_document.summary || ' ' || entry_document.content, query, $1) FROM entry_document JOIN entry ON entry.id = entry_document.id, plainto_tsquery('english', $2) query WHERE entry_document.document @@ query",
+        );
+        let mut params: Vec<Box<dyn ToSql + Sync>> =
+            vec![Box::new(options), Box::new(query.text().to_string())];
+
+        if !query.members().is_empty() {
+            params.push(Box::new(query.members().to_vec()));
+            sql.push_str(&format!(" AND entry.author = ANY( ${} )", params.len()));
+        }
+
+        if let Some(start_date) = query.start_date() {
+            params.push(Box::new(start_date));
+            sql.push_str(&format!(" AND entry.published >= ${}", params.len()));
+        }
+
+        if let Some(end_date) = query.end_date() {
+            params.push(Box::new(end_date));
+            sql.push_str(&format!(" AND entry.published <= ${}", params.len()));
+        }
+
+        if let Some(application) = query.application() {
+            params.push(Box::new(application.clone()));
+            sql.push_str(&format!(
+                " AND entry_document.application = ${}",
+                params.len()
+            ));
+        }
+
+        if let Some(object_type) = query.object_type() {
+            params.push(Box::new(object_type.clone()));
+            sql.push_str(&format!(
+                " AND position(' ' || ${}::TEXT || ' ' IN ' ' || entry_document.object_types || ' ') > 0",
+                params.len()
+            ));
+        }
+
+        params.push(Box::new(query.limit() as i64));
+        sql.push_str(&format!(
+            " ORDER BY ts_rank(entry_document.document, query) DESC LIMIT ${}",
+            params.len()
+        ));
+
+        let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|param| param.as_ref()).collect();
+        let rows = self
+            .client()?
+            .query(sql.as_str(), &params)
+            .map_err(DatabaseError::next_failed)?;
+        let mut result = Vec::new();
+
+        for row in &rows {
+            result.push(SearchResult::new(
+                value(row, 0)?,
+                value(row, 1)?,
+                value(row, 2)?,
+            ));
+        }
+
+        Ok(result)
+    }
+
+    fn delete_published_before(&self, time: i64) -> DatabaseResult<usize> {
+        let mut client = self.client()?;
+        let mut transaction = transaction(&mut client)?;
+
+        transaction
+            .execute(
+                &format!(
+                    "DELETE FROM entry_revision WHERE id IN ( {} )",
+                    EXPIRED_ENTRIES
+                ),
+                &[&time],
+            )
+            .map_err(DatabaseError::execution_error)?;
+        transaction
+            .execute(
+                &format!(
+                    "DELETE FROM entry_document WHERE id IN ( {} )",
+                    EXPIRED_ENTRIES
+                ),
+                &[&time],
+            )
+            .map_err(DatabaseError::execution_error)?;
+        transaction
+            .execute(
+                &format!(
+                    "DELETE FROM entry_issue WHERE id IN ( {} )",
+                    EXPIRED_ENTRIES
+                ),
+                &[&time],
+            )
+            .map_err(DatabaseError::execution_error)?;
+
+        let deleted = transaction
+            .execute(
+                &format!("DELETE FROM entry WHERE id IN ( {} )", EXPIRED_ENTRIES),
+                &[&time],
+            )
+            .map_err(DatabaseError::execution_error)?;
+
+        transaction
+            .commit()
+            .map_err(DatabaseError::execution_error)?;
+
+        Ok(deleted as usize)
+    }
+}
+
+fn open(url: &str, connector: &TlsConnector) -> DatabaseResult<Client> {
+    Client::connect(url, MakeTlsConnector::new(connector.clone()))
+        .map_err(DatabaseError::connection_error)
+}
+
+/// Applies all schema updates newer than current database version. Empty database has version 0.
+/// Updates and indexing of stored entries run in single transaction, so version is not changed
+/// unless all succeed.
+fn update_database(client: &mut Client) -> DatabaseResult<()> {
+    let version = version(client)?;
+    let latest = match UPDATES.last() {
+        Some(&(latest, _)) if version < latest => latest,
+        _ => return Ok(()),
+    };
+    let mut transaction = transaction(client)?;
+
+    for &(update_version, script) in UPDATES {
+        if version < update_version {
+            info!("Updating database to version {}", update_version);
+
+            transaction
+                .batch_execute(script)
+                .map_err(DatabaseError::execution_error)?;
+        }
+    }
+
+    if version < INDEX_VERSION {
+        index_all(&mut transaction)?;
+    }
+
+    transaction
+        .execute("DELETE FROM schema_version", &[])
+        .map_err(DatabaseError::execution_error)?;
+    transaction
+        .execute(
+            "INSERT INTO schema_version ( version ) VALUES ( $1 )",
+            &[&latest],
+        )
+        .map_err(DatabaseError::execution_error)?;
+    transaction.commit().map_err(DatabaseError::execution_error)
+}
+
+/// Adds all stored entries to search and issue indexes.
+fn index_all(transaction: &mut Transaction<'_>) -> DatabaseResult<()> {
+    let mut last_id = String::new();
+
+    loop {
+        let rows = transaction
+            .query(
+                "SELECT id, data FROM entry WHERE id > $1 ORDER BY id LIMIT $2",
+                &[&last_id, &INDEX_PAGE_SIZE],
+            )
+            .map_err(DatabaseError::next_failed)?;
+
+        for row in &rows {
+            index_entry(transaction, value(row, 0)?, value(row, 1)?)?;
+        }
+
+        match rows.last() {
+            Some(row) => last_id = value(row, 0)?,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Replaces search document and issue references of entry, entry which can not be parsed is not
+/// indexed.
+fn index_entry<C>(client: &mut C, id: &str, data: &str) -> DatabaseResult<()>
+where
+    C: GenericClient,
+{
+    client
+        .execute("DELETE FROM entry_document WHERE id = $1", &[&id])
+        .map_err(DatabaseError::execution_error)?;
+    client
+        .execute("DELETE FROM entry_issue WHERE id = $1", &[&id])
+        .map_err(DatabaseError::execution_error)?;
+
+    if let Some(document) = SearchDocument::from_data(data) {
+        client
+            .execute(
+                "INSERT INTO entry_document ( id, application, object_types, title, summary, content ) VALUES ( $1, $2, $3, $4, $5, $6 )",
+                &[
+                    &id,
+                    &document.application(),
+                    &document.object_types(),
+                    &document.title(),
+                    &document.summary(),
+                    &document.content(),
+                ],
+            )
+            .map_err(DatabaseError::execution_error)?;
+
+        for issue_key in document.issue_keys() {
+            client
+                .execute(
+                    "INSERT INTO entry_issue ( issue_key, id ) VALUES ( $1, $2 )",
+                    &[issue_key, &id],
+                )
+                .map_err(DatabaseError::execution_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn version(client: &mut Client) -> DatabaseResult<i32> {
+    let row = client
+        .query_one("SELECT to_regclass('schema_version') IS NOT NULL", &[])
+        .map_err(DatabaseError::next_failed)?;
+
+    if !value::<bool>(&row, 0)? {
+        return Ok(0);
+    }
+
+    let row = client
+        .query_one("SELECT MAX(version) FROM schema_version", &[])
+        .map_err(DatabaseError::next_failed)?;
+
+    Ok(value::<Option<i32>>(&row, 0)?.unwrap_or(0))
+}
+
+/// Starts transaction, it is rolled back if dropped without commit.
+fn transaction(client: &mut Client) -> DatabaseResult<Transaction<'_>> {
+    client.transaction().map_err(DatabaseError::execution_error)
+}
+
+/// Returns value of given column converted to requested type.
+fn value<'a, T>(row: &'a Row, index: usize) -> DatabaseResult<T>
+where
+    T: FromSql<'a>,
+{
+    row.try_get(index).map_err(|err| {
+        warn!("Failed to read column {}: {}", index, err);
+
+        DatabaseError::no_such_value()
+    })
+}
+
+impl Debug for PostgresStorage {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "PostgreSQL database")
+    }
+}
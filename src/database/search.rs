@@ -0,0 +1,211 @@
+use serde_yaml;
+
+use entity::Entry;
+
+/// Marks start and end of matched words in snippets.
+pub const HIGHLIGHT: &str = "**";
+/// Approximate number of words in snippet.
+pub const SNIPPET_WORDS: usize = 16;
+
+/// Searchable text and filter values of stored entry.
+#[derive(Debug, Clone)]
+pub struct SearchDocument {
+    application: String,
+    object_types: String,
+    issue_keys: Vec<String>,
+    title: String,
+    summary: String,
+    content: String,
+}
+
+/// Full-text query with optional filters. Entry matches if all words are found in its title,
+/// summaries of its objects or content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    text: String,
+    members: Vec<String>,
+    start_date: Option<i64>,
+    end_date: Option<i64>,
+    application: Option<String>,
+    object_type: Option<String>,
+    limit: usize,
+}
+
+/// Entry found by search.
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    author: String,
+    data: String,
+    snippet: String,
+}
+
+impl SearchDocument {
+    /// Extracts searchable text from serialized entry, returns `None` if data is not valid entry.
+    pub fn from_data(data: &str) -> Option<SearchDocument> {
+        let entry: Entry = match serde_yaml::from_str(data) {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("Failed to index entry: {}", err);
+
+                return None;
+            }
+        };
+        let objects: Vec<_> = entry.objects().iter().chain(entry.target()).collect();
+        let mut object_types: Vec<&str> = Vec::with_capacity(objects.len());
+
+        for object in &objects {
+            let object_type = type_name(object.object_type());
+
+            if !object_types.contains(&object_type) {
+                object_types.push(object_type);
+            }
+        }
+
+        let mut issue_keys: Vec<String> = Vec::new();
+
+        for key in entry.issue_keys() {
+            if !issue_keys.iter().any(|k| k == key.key()) {
+                issue_keys.push(key.key().into());
+            }
+        }
+
+        let summaries: Vec<&str> = objects.iter().filter_map(|o| o.summary()).collect();
+
+        Some(SearchDocument {
+            application: entry.application().into(),
+            object_types: object_types.join(" "),
+            issue_keys,
+            title: entry.title_text(),
+            summary: summaries.join("\n"),
+            content: entry
+                .content_text()
+                .map(|content| content.text().into())
+                .unwrap_or_default(),
+        })
+    }
+
+    pub fn application(&self) -> &str {
+        &self.application
+    }
+
+    /// Returns distinct short type names of entry objects and target separated with spaces.
+    pub fn object_types(&self) -> &str {
+        &self.object_types
+    }
+
+    /// Returns distinct keys of issues entry objects and target refer to.
+    pub fn issue_keys(&self) -> &[String] {
+        &self.issue_keys
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+impl SearchQuery {
+    pub fn new(text: &str, limit: usize) -> SearchQuery {
+        SearchQuery {
+            text: text.into(),
+            members: Vec::with_capacity(0),
+            start_date: None,
+            end_date: None,
+            application: None,
+            object_type: None,
+            limit,
+        }
+    }
+
+    pub fn with_members(mut self, members: &[String]) -> SearchQuery {
+        self.members = members.into();
+        self
+    }
+
+    /// Limits results to entries published in given UNIX time interval.
+    pub fn with_interval(mut self, start_date: Option<i64>, end_date: Option<i64>) -> SearchQuery {
+        self.start_date = start_date;
+        self.end_date = end_date;
+        self
+    }
+
+    pub fn with_application(mut self, application: Option<&String>) -> SearchQuery {
+        self.application = application.cloned();
+        self
+    }
+
+    /// Limits results to entries with object or target of given type, e.g. `issue`.
+    pub fn with_object_type(mut self, object_type: Option<&String>) -> SearchQuery {
+        self.object_type = object_type.cloned();
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns words of query text.
+    pub fn words(&self) -> Vec<&str> {
+        self.text.split_whitespace().collect()
+    }
+
+    pub fn members(&self) -> &[String] {
+        &self.members
+    }
+
+    pub fn start_date(&self) -> Option<i64> {
+        self.start_date
+    }
+
+    pub fn end_date(&self) -> Option<i64> {
+        self.end_date
+    }
+
+    pub fn application(&self) -> Option<&String> {
+        self.application.as_ref()
+    }
+
+    pub fn object_type(&self) -> Option<&String> {
+        self.object_type.as_ref()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl SearchResult {
+    pub fn new(author: &str, data: &str, snippet: &str) -> SearchResult {
+        SearchResult {
+            author: author.into(),
+            data: data.into(),
+            snippet: snippet.into(),
+        }
+    }
+
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    /// Returns serialized entry.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Returns part of matched text with matched words enclosed in `**`.
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+}
+
+/// Returns last segment of object type URI, e.g. `issue` or `pull-request`.
+pub fn type_name(object_type: &str) -> &str {
+    object_type.rsplit('/').next().unwrap_or(object_type)
+}
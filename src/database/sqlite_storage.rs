@@ -0,0 +1,929 @@
+use sqlite;
+use sqlite::Connection;
+use sqlite::Value;
+use sqlite3_sys as ffi;
+use std::ffi::CStr;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::pool::lock;
+use super::pool::Pool;
+use super::search::SearchDocument;
+use super::search::HIGHLIGHT;
+use super::search::SNIPPET_WORDS;
+use super::DatabaseError;
+use super::DatabaseResult;
+use super::EntryData;
+use super::FeedQuery;
+use super::RevisionData;
+use super::SaveStats;
+use super::SearchQuery;
+use super::SearchResult;
+use super::Storage;
+
+/// Schema updates, every item contains database version and script to reach it.
+const UPDATES: &[(i64, &str)] = &[
+    (2, include_str!("update_database_v2.sql")),
+    (3, include_str!("update_database_v3.sql")),
+    (4, include_str!("update_database_v4.sql")),
+    (5, include_str!("update_database_v5.sql")),
+];
+/// Latest version adding index of entry data, entries stored before are indexed after update.
+const INDEX_VERSION: i64 = 5;
+/// Number of entries indexed at once.
+const INDEX_PAGE_SIZE: usize = 500;
+
+/// Time in milliseconds to wait for database locked by other connection.
+const BUSY_TIMEOUT: usize = 30_000;
+
+/// Entries published before given time except newest entry of every member, which is kept as
+/// position of next update.
+const EXPIRED_ENTRIES: &str = "SELECT id FROM entry WHERE published < ? AND published < \
+     ( SELECT MAX(published) FROM entry AS newest WHERE newest.author = entry.author )";
+
+/// Name of database copied by backup, terminated for SQLite C API.
+const MAIN_DATABASE: &[u8] = b"main\0";
+
+/// Storage in SQLite database file opened in WAL mode. Writes are serialized through single
+/// writer connection, reads and snapshots use pool of reader connections and are not blocked by
+/// writes.
+pub struct SqliteStorage {
+    writer: Mutex<SqliteConnection>,
+    readers: Pool<SqliteConnection>,
+}
+
+struct SqliteConnection {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    /// Opens database file with given number of reader connections, database is created or
+    /// updated to current schema if needed.
+    pub fn open(path: &str, readers: usize) -> DatabaseResult<SqliteStorage> {
+        let initialize = !Path::new(path).exists();
+        let mut writer = SqliteConnection::open(path)?;
+
+        writer.execute("PRAGMA journal_mode = WAL")?;
+        writer.execute("PRAGMA synchronous = NORMAL")?;
+
+        if initialize {
+            writer.create_database()?;
+        }
+
+        writer.update_database()?;
+
+        let mut connections = Vec::with_capacity(readers);
+
+        for _ in 0..readers.max(1) {
+            connections.push(SqliteConnection::open(path)?);
+        }
+
+        Ok(SqliteStorage {
+            writer: Mutex::new(writer),
+            readers: Pool::new(connections),
+        })
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn save_entries(
+        &self,
+        author: &str,
+        entries: &mut dyn Iterator<Item = DatabaseResult<EntryData>>,
+    ) -> DatabaseResult<SaveStats> {
+        lock(&self.writer).save_entries(author, entries)
+    }
+
+    fn last_published(&self, author: &str) -> DatabaseResult<Option<i64>> {
+        self.readers.get().last_published(author)
+    }
+
+    fn published_between(
+        &self,
+        author: &str,
+        start_date: i64,
+        end_date: i64,
+    ) -> DatabaseResult<Vec<String>> {
+        self.readers
+            .get()
+            .published_between(author, start_date, end_date)
+    }
+
+    fn feed_entries(&self, query: &FeedQuery) -> DatabaseResult<Vec<(String, String)>> {
+        self.readers.get().feed_entries(query)
+    }
+
+    fn entry_count(&self) -> DatabaseResult<usize> {
+        self.readers.get().count("entry")
+    }
+
+    fn revision_count(&self) -> DatabaseResult<usize> {
+        self.readers.get().count("entry_revision")
+    }
+
+    fn entries_after(
+        &self,
+        last_id: &str,
+        limit: usize,
+    ) -> DatabaseResult<Vec<(String, EntryData)>> {
+        self.readers.get().entries_after(last_id, limit)
+    }
+
+    fn revisions_after(
+        &self,
+        last_id: &str,
+        last_updated: i64,
+        limit: usize,
+    ) -> DatabaseResult<Vec<RevisionData>> {
+        self.readers
+            .get()
+            .revisions_after(last_id, last_updated, limit)
+    }
+
+    fn restore(
+        &self,
+        entries: &[(String, EntryData)],
+        revisions: &[RevisionData],
+    ) -> DatabaseResult<()> {
+        lock(&self.writer).restore(entries, revisions)
+    }
+
+    fn backup(&self, path: &str) -> DatabaseResult<()> {
+        self.readers.get().backup(path)
+    }
+
+    fn issue_entries(&self, issue_key: &str) -> DatabaseResult<Vec<(String, String)>> {
+        self.readers.get().issue_entries(issue_key)
+    }
+
+    fn search(&self, query: &SearchQuery) -> DatabaseResult<Vec<SearchResult>> {
+        self.readers.get().search(query)
+    }
+
+    fn delete_published_before(&self, time: i64) -> DatabaseResult<usize> {
+        lock(&self.writer).delete_published_before(time)
+    }
+}
+
+impl SqliteConnection {
+    /// Opens connection waiting for locks held by other connections.
+    fn open(path: &str) -> DatabaseResult<SqliteConnection> {
+        let mut connection = sqlite::open(path).map_err(DatabaseError::connection_error)?;
+
+        connection
+            .set_busy_timeout(BUSY_TIMEOUT)
+            .map_err(DatabaseError::connection_error)?;
+
+        Ok(SqliteConnection { connection })
+    }
+
+    fn execute(&self, statement: &str) -> DatabaseResult<()> {
+        self.connection
+            .execute(statement)
+            .map_err(DatabaseError::execution_error)
+    }
+
+    fn create_database(&mut self) -> DatabaseResult<()> {
+        self.connection
+            .execute(include_str!("init_database.sql"))
+            .map_err(DatabaseError::execution_error)?;
+
+        self.set_version(1)
+    }
+
+    /// Applies all schema updates newer than current database version. Databases created
+    /// before versioning have version 0 and same schema as version 1. Updates and indexing of
+    /// stored entries run in single transaction, so version is not changed unless all succeed.
+    fn update_database(&mut self) -> DatabaseResult<()> {
+        let version = self.version()?;
+        let latest = match UPDATES.last() {
+            Some(&(latest, _)) if version < latest => latest,
+            _ => return Ok(()),
+        };
+
+        self.in_transaction(|inner| {
+            for &(update_version, script) in UPDATES {
+                if version < update_version {
+                    info!("Updating database to version {}", update_version);
+
+                    inner
+                        .connection
+                        .execute(script)
+                        .map_err(DatabaseError::execution_error)?;
+                }
+            }
+
+            if version < INDEX_VERSION {
+                inner.index_all()?;
+            }
+
+            inner.set_version(latest)
+        })
+    }
+
+    /// Adds all stored entries to search and issue indexes, must be called in transaction.
+    fn index_all(&self) -> DatabaseResult<()> {
+        let mut last_id = String::new();
+
+        loop {
+            let entries = self.entries_after(&last_id, INDEX_PAGE_SIZE)?;
+
+            for (_, entry) in &entries {
+                self.index_entry(entry.id(), entry.data())?;
+            }
+
+            match entries.last() {
+                Some((_, entry)) => last_id = entry.id().into(),
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Replaces search document and issue references of entry, entry which can not be parsed is
+    /// not indexed.
+    fn index_entry(&self, id: &str, data: &str) -> DatabaseResult<()> {
+        for table in &["entry_document", "entry_issue"] {
+            let mut statement = self
+                .connection
+                .prepare(format!("DELETE FROM {} WHERE id = ?", table))
+                .map_err(DatabaseError::prepare_failed)?;
+            statement.bind(1, id).map_err(DatabaseError::bind_failed)?;
+            statement.next().map_err(DatabaseError::next_failed)?;
+        }
+
+        let document = match SearchDocument::from_data(data) {
+            Some(document) => document,
+            None => return Ok(()),
+        };
+        let mut statement = self
+            .connection
+            .prepare(
+                "INSERT INTO entry_document ( id, application, object_types, title, summary, content ) VALUES ( ?, ?, ?, ?, ?, ? )",
+            )
+            .map_err(DatabaseError::prepare_failed)?;
+        statement.bind(1, id).map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(2, document.application())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(3, document.object_types())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(4, document.title())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(5, document.summary())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(6, document.content())
+            .map_err(DatabaseError::bind_failed)?;
+        statement.next().map_err(DatabaseError::next_failed)?;
+
+        for issue_key in document.issue_keys() {
+            let mut statement = self
+                .connection
+                .prepare("INSERT INTO entry_issue ( issue_key, id ) VALUES ( ?, ? )")
+                .map_err(DatabaseError::prepare_failed)?;
+            statement
+                .bind(1, issue_key.as_str())
+                .map_err(DatabaseError::bind_failed)?;
+            statement.bind(2, id).map_err(DatabaseError::bind_failed)?;
+            statement.next().map_err(DatabaseError::next_failed)?;
+        }
+
+        Ok(())
+    }
+
+    fn version(&self) -> DatabaseResult<i64> {
+        let statement = self
+            .connection
+            .prepare("PRAGMA user_version")
+            .map_err(DatabaseError::prepare_failed)?;
+        let mut cursor = statement.cursor();
+
+        if let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            row.get(0)
+                .ok_or_else(DatabaseError::no_such_column)?
+                .as_integer()
+                .ok_or_else(DatabaseError::no_such_value)
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn set_version(&self, version: i64) -> DatabaseResult<()> {
+        self.connection
+            .execute(format!("PRAGMA user_version = {}", version))
+            .map_err(DatabaseError::execution_error)
+    }
+
+    /// Runs callback in write transaction. Write lock is taken at start, so busy timeout applies
+    /// instead of failing when transaction upgrades from read to write.
+    fn in_transaction<T, F>(&mut self, callback: F) -> DatabaseResult<T>
+    where
+        F: FnOnce(&mut SqliteConnection) -> DatabaseResult<T>,
+    {
+        self.connection
+            .execute("BEGIN IMMEDIATE TRANSACTION")
+            .map_err(DatabaseError::execution_error)?;
+
+        let result = callback(self).and_then(|result| {
+            self.connection
+                .execute("COMMIT")
+                .map_err(DatabaseError::execution_error)?;
+
+            Ok(result)
+        });
+
+        // Failed commit, e.g. of busy database, leaves transaction open.
+        if result.is_err() {
+            if let Err(err) = self.connection.execute("ROLLBACK") {
+                warn!("Failed to rollback transaction: {}", err);
+            }
+        }
+
+        result
+    }
+
+    fn entry_updated(&self, id: &str) -> DatabaseResult<Option<i64>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT updated FROM entry WHERE id = ?")
+            .map_err(DatabaseError::prepare_failed)?;
+        statement.bind(1, id).map_err(DatabaseError::bind_failed)?;
+        let mut cursor = statement.cursor();
+
+        if let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            let updated = row
+                .get(0)
+                .ok_or_else(DatabaseError::no_such_column)?
+                .as_integer()
+                .ok_or_else(DatabaseError::no_such_value)?;
+
+            Ok(Some(updated))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn insert_entry(&self, author: &str, entry: &EntryData) -> DatabaseResult<()> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "INSERT OR IGNORE INTO entry ( id, author, published, updated, data ) VALUES ( ?, ?, ?, ?, ? )",
+            )
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, entry.id())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(2, author)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(3, entry.published())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(4, entry.updated())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(5, entry.data())
+            .map_err(DatabaseError::bind_failed)?;
+        statement.next().map_err(DatabaseError::next_failed)?;
+
+        Ok(())
+    }
+
+    fn archive_entry(&self, id: &str) -> DatabaseResult<()> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "INSERT OR IGNORE INTO entry_revision ( id, updated, data ) SELECT id, updated, data FROM entry WHERE id = ?",
+            )
+            .map_err(DatabaseError::prepare_failed)?;
+        statement.bind(1, id).map_err(DatabaseError::bind_failed)?;
+        statement.next().map_err(DatabaseError::next_failed)?;
+
+        Ok(())
+    }
+
+    fn update_entry(&self, entry: &EntryData) -> DatabaseResult<()> {
+        let mut statement = self
+            .connection
+            .prepare("UPDATE entry SET updated = ?, data = ? WHERE id = ?")
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, entry.updated())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(2, entry.data())
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(3, entry.id())
+            .map_err(DatabaseError::bind_failed)?;
+        statement.next().map_err(DatabaseError::next_failed)?;
+
+        Ok(())
+    }
+
+    /// Returns number of rows in given table.
+    fn count(&self, table: &str) -> DatabaseResult<usize> {
+        let statement = self
+            .connection
+            .prepare(format!("SELECT COUNT(*) FROM {}", table))
+            .map_err(DatabaseError::prepare_failed)?;
+        let mut cursor = statement.cursor();
+
+        match cursor.next().map_err(DatabaseError::next_failed)? {
+            Some(row) => Ok(integer_value(row, 0)? as usize),
+            None => Ok(0),
+        }
+    }
+
+    fn save_entries(
+        &mut self,
+        author: &str,
+        entries: &mut dyn Iterator<Item = DatabaseResult<EntryData>>,
+    ) -> DatabaseResult<SaveStats> {
+        self.in_transaction(|inner| {
+            let mut stats = SaveStats::default();
+
+            for entry in entries {
+                let entry = entry?;
+
+                match inner.entry_updated(entry.id())? {
+                    None => {
+                        inner.insert_entry(author, &entry)?;
+                        inner.index_entry(entry.id(), entry.data())?;
+                        stats.add_inserted();
+                    }
+                    Some(updated) if updated < entry.updated() => {
+                        inner.archive_entry(entry.id())?;
+                        inner.update_entry(&entry)?;
+                        inner.index_entry(entry.id(), entry.data())?;
+                        stats.add_updated();
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            Ok(stats)
+        })
+    }
+
+    fn last_published(&self, author: &str) -> DatabaseResult<Option<i64>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT MAX(published) FROM entry WHERE author = ?")
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, author)
+            .map_err(DatabaseError::bind_failed)?;
+        let mut cursor = statement.cursor();
+
+        if let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            let published = row
+                .get(0)
+                .ok_or_else(DatabaseError::no_such_column)?
+                .as_integer();
+
+            Ok(published)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn published_between(
+        &self,
+        author: &str,
+        start_date: i64,
+        end_date: i64,
+    ) -> DatabaseResult<Vec<String>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT data FROM entry WHERE author = ? AND published BETWEEN ? AND ?")
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, author)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(2, start_date)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(3, end_date)
+            .map_err(DatabaseError::bind_failed)?;
+        let mut cursor = statement.cursor();
+        let mut result = Vec::new();
+
+        while let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            let data = row
+                .get(0)
+                .ok_or_else(DatabaseError::no_such_column)?
+                .as_string()
+                .ok_or_else(DatabaseError::no_such_value)?
+                .into();
+
+            result.push(data);
+        }
+
+        Ok(result)
+    }
+
+    fn feed_entries(&self, query: &FeedQuery) -> DatabaseResult<Vec<(String, String)>> {
+        let mut sql = String::from("SELECT entry.author, entry.data FROM entry");
+        let mut values = Vec::new();
+
+        if query.application().is_some() {
+            sql.push_str(" JOIN entry_document ON entry_document.id = entry.id");
+        }
+
+        sql.push_str(" WHERE entry.published BETWEEN ? AND ?");
+        values.push(Value::Integer(query.start_date()));
+        values.push(Value::Integer(query.end_date()));
+
+        if !query.members().is_empty() {
+            let placeholders = vec!["?"; query.members().len()].join(", ");
+
+            sql.push_str(&format!(" AND entry.author IN ( {} )", placeholders));
+            values.extend(query.members().iter().cloned().map(Value::String));
+        }
+
+        if let Some(application) = query.application() {
+            sql.push_str(" AND entry_document.application = ?");
+            values.push(Value::String(application.clone()));
+        }
+
+        sql.push_str(" ORDER BY entry.published DESC, entry.id LIMIT ? OFFSET ?");
+        values.push(Value::Integer(query.limit() as i64));
+        values.push(Value::Integer(query.offset() as i64));
+
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(DatabaseError::prepare_failed)?;
+
+        for (index, value) in values.iter().enumerate() {
+            statement
+                .bind(index + 1, value)
+                .map_err(DatabaseError::bind_failed)?;
+        }
+
+        let mut cursor = statement.cursor();
+        let mut result = Vec::new();
+
+        while let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            result.push((string_value(row, 0)?.into(), string_value(row, 1)?.into()));
+        }
+
+        Ok(result)
+    }
+
+    fn entries_after(
+        &self,
+        last_id: &str,
+        limit: usize,
+    ) -> DatabaseResult<Vec<(String, EntryData)>> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT id, author, published, updated, data FROM entry WHERE id > ? ORDER BY id LIMIT ?",
+            )
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, last_id)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(2, limit as i64)
+            .map_err(DatabaseError::bind_failed)?;
+        let mut cursor = statement.cursor();
+        let mut result = Vec::new();
+
+        while let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            let entry = EntryData::new(
+                string_value(row, 0)?,
+                integer_value(row, 2)?,
+                integer_value(row, 3)?,
+                string_value(row, 4)?,
+            );
+
+            result.push((string_value(row, 1)?.into(), entry));
+        }
+
+        Ok(result)
+    }
+
+    fn revisions_after(
+        &self,
+        last_id: &str,
+        last_updated: i64,
+        limit: usize,
+    ) -> DatabaseResult<Vec<RevisionData>> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT id, updated, data FROM entry_revision WHERE id > ? OR ( id = ? AND updated > ? ) ORDER BY id, updated LIMIT ?",
+            )
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, last_id)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(2, last_id)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(3, last_updated)
+            .map_err(DatabaseError::bind_failed)?;
+        statement
+            .bind(4, limit as i64)
+            .map_err(DatabaseError::bind_failed)?;
+        let mut cursor = statement.cursor();
+        let mut result = Vec::new();
+
+        while let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            result.push(RevisionData::new(
+                string_value(row, 0)?,
+                integer_value(row, 1)?,
+                string_value(row, 2)?,
+            ));
+        }
+
+        Ok(result)
+    }
+
+    fn restore(
+        &mut self,
+        entries: &[(String, EntryData)],
+        revisions: &[RevisionData],
+    ) -> DatabaseResult<()> {
+        self.in_transaction(|inner| {
+            for (author, entry) in entries {
+                let mut statement = inner
+                    .connection
+                    .prepare(
+                        "INSERT INTO entry ( id, author, published, updated, data ) VALUES ( ?, ?, ?, ?, ? )",
+                    )
+                    .map_err(DatabaseError::prepare_failed)?;
+                statement
+                    .bind(1, entry.id())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement
+                    .bind(2, author.as_str())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement
+                    .bind(3, entry.published())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement
+                    .bind(4, entry.updated())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement
+                    .bind(5, entry.data())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement.next().map_err(DatabaseError::next_failed)?;
+
+                inner.index_entry(entry.id(), entry.data())?;
+            }
+
+            for revision in revisions {
+                let mut statement = inner
+                    .connection
+                    .prepare("INSERT INTO entry_revision ( id, updated, data ) VALUES ( ?, ?, ? )")
+                    .map_err(DatabaseError::prepare_failed)?;
+                statement
+                    .bind(1, revision.id())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement
+                    .bind(2, revision.updated())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement
+                    .bind(3, revision.data())
+                    .map_err(DatabaseError::bind_failed)?;
+                statement.next().map_err(DatabaseError::next_failed)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn issue_entries(&self, issue_key: &str) -> DatabaseResult<Vec<(String, String)>> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT entry.author, entry.data FROM entry_issue JOIN entry ON entry.id = entry_issue.id WHERE entry_issue.issue_key = ? ORDER BY entry.published, entry.id",
+            )
+            .map_err(DatabaseError::prepare_failed)?;
+        statement
+            .bind(1, issue_key)
+            .map_err(DatabaseError::bind_failed)?;
+        let mut cursor = statement.cursor();
+        let mut result = Vec::new();
+
+        while let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            result.push((string_value(row, 0)?.into(), string_value(row, 1)?.into()));
+        }
+
+        Ok(result)
+    }
+
+    fn search(&self, query: &SearchQuery) -> DatabaseResult<Vec<SearchResult>> {
+        let words = query.words();
+
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut sql = String::from(
+            "SELECT entry.author, entry.data, snippet(entry_search, -1, ?, ?, '…', ?) FROM entry_search JOIN entry_document ON entry_document.rowid = entry_search.rowid JOIN entry ON entry.id = entry_document.id WHERE entry_search MATCH ?",
+        );
+        let mut values = vec![
+            Value::String(HIGHLIGHT.into()),
+            Value::String(HIGHLIGHT.into()),
+            Value::Integer(SNIPPET_WORDS as i64),
+            Value::String(match_expression(&words)),
+        ];
+
+        if !query.members().is_empty() {
+            let placeholders = vec!["?"; query.members().len()].join(", ");
+
+            sql.push_str(&format!(" AND entry.author IN ( {} )", placeholders));
+            values.extend(query.members().iter().cloned().map(Value::String));
+        }
+
+        if let Some(start_date) = query.start_date() {
+            sql.push_str(" AND entry.published >= ?");
+            values.push(Value::Integer(start_date));
+        }
+
+        if let Some(end_date) = query.end_date() {
+            sql.push_str(" AND entry.published <= ?");
+            values.push(Value::Integer(end_date));
+        }
+
+        if let Some(application) = query.application() {
+            sql.push_str(" AND entry_document.application = ?");
+            values.push(Value::String(application.clone()));
+        }
+
+        if let Some(object_type) = query.object_type() {
+            sql.push_str(
+                " AND instr(' ' || entry_document.object_types || ' ', ' ' || ? || ' ') > 0",
+            );
+            values.push(Value::String(object_type.clone()));
+        }
+
+        sql.push_str(" ORDER BY rank LIMIT ?");
+        values.push(Value::Integer(query.limit() as i64));
+
+        let mut statement = self
+            .connection
+            .prepare(sql)
+            .map_err(DatabaseError::prepare_failed)?;
+
+        for (index, value) in values.iter().enumerate() {
+            statement
+                .bind(index + 1, value)
+                .map_err(DatabaseError::bind_failed)?;
+        }
+
+        let mut cursor = statement.cursor();
+        let mut result = Vec::new();
+
+        while let Some(row) = cursor.next().map_err(DatabaseError::next_failed)? {
+            result.push(SearchResult::new(
+                string_value(row, 0)?,
+                string_value(row, 1)?,
+                string_value(row, 2)?,
+            ));
+        }
+
+        Ok(result)
+    }
+
+    /// Writes consistent copy of database to given path with SQLite online backup API, file must
+    /// not exist.
+    fn backup(&self, path: &str) -> DatabaseResult<()> {
+        if Path::new(path).exists() {
+            return Err(DatabaseError::backup_failed(&format!(
+                "file `{}` already exists",
+                path
+            )));
+        }
+
+        let target = sqlite::open(path).map_err(DatabaseError::connection_error)?;
+
+        unsafe {
+            let backup = ffi::sqlite3_backup_init(
+                target.as_raw(),
+                MAIN_DATABASE.as_ptr() as *const c_char,
+                self.connection.as_raw(),
+                MAIN_DATABASE.as_ptr() as *const c_char,
+            );
+
+            if backup.is_null() {
+                return Err(DatabaseError::backup_failed(&last_error(target.as_raw())));
+            }
+
+            let step = ffi::sqlite3_backup_step(backup, -1);
+            let finish = ffi::sqlite3_backup_finish(backup);
+
+            if step != ffi::SQLITE_DONE || finish != ffi::SQLITE_OK {
+                return Err(DatabaseError::backup_failed(&last_error(target.as_raw())));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn delete_published_before(&mut self, time: i64) -> DatabaseResult<usize> {
+        self.in_transaction(|inner| {
+            let mut statement = inner
+                .connection
+                .prepare(format!(
+                    "DELETE FROM entry_revision WHERE id IN ( {} )",
+                    EXPIRED_ENTRIES
+                ))
+                .map_err(DatabaseError::prepare_failed)?;
+            statement
+                .bind(1, time)
+                .map_err(DatabaseError::bind_failed)?;
+            statement.next().map_err(DatabaseError::next_failed)?;
+
+            for table in &["entry_document", "entry_issue"] {
+                let mut statement = inner
+                    .connection
+                    .prepare(format!(
+                        "DELETE FROM {} WHERE id IN ( {} )",
+                        table, EXPIRED_ENTRIES
+                    ))
+                    .map_err(DatabaseError::prepare_failed)?;
+                statement
+                    .bind(1, time)
+                    .map_err(DatabaseError::bind_failed)?;
+                statement.next().map_err(DatabaseError::next_failed)?;
+            }
+
+            let mut statement = inner
+                .connection
+                .prepare(format!(
+                    "DELETE FROM entry WHERE id IN ( {} )",
+                    EXPIRED_ENTRIES
+                ))
+                .map_err(DatabaseError::prepare_failed)?;
+            statement
+                .bind(1, time)
+                .map_err(DatabaseError::bind_failed)?;
+            statement.next().map_err(DatabaseError::next_failed)?;
+
+            let statement = inner
+                .connection
+                .prepare("SELECT changes()")
+                .map_err(DatabaseError::prepare_failed)?;
+            let mut cursor = statement.cursor();
+
+            match cursor.next().map_err(DatabaseError::next_failed)? {
+                Some(row) => Ok(integer_value(row, 0)? as usize),
+                None => Ok(0),
+            }
+        })
+    }
+}
+
+/// Returns FTS5 query matching entries containing all words. Words are quoted, so punctuation
+/// like in issue keys is not parsed as query syntax.
+fn match_expression(words: &[&str]) -> String {
+    let phrases: Vec<String> = words
+        .iter()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+
+    phrases.join(" ")
+}
+
+/// Returns message of last error of raw connection.
+unsafe fn last_error(connection: *mut ffi::sqlite3) -> String {
+    CStr::from_ptr(ffi::sqlite3_errmsg(connection))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn string_value(row: &[Value], index: usize) -> DatabaseResult<&str> {
+    row.get(index)
+        .ok_or_else(DatabaseError::no_such_column)?
+        .as_string()
+        .ok_or_else(DatabaseError::no_such_value)
+}
+
+fn integer_value(row: &[Value], index: usize) -> DatabaseResult<i64> {
+    row.get(index)
+        .ok_or_else(DatabaseError::no_such_column)?
+        .as_integer()
+        .ok_or_else(DatabaseError::no_such_value)
+}
+
+impl Debug for SqliteStorage {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "SQLite database")
+    }
+}
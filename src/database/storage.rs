@@ -0,0 +1,83 @@
+use std::fmt::Debug;
+
+use super::DatabaseError;
+use super::DatabaseResult;
+use super::EntryData;
+use super::FeedQuery;
+use super::RevisionData;
+use super::SaveStats;
+use super::SearchQuery;
+use super::SearchResult;
+
+/// Database backend storing entries and their revision history. Implementations are shared
+/// between threads and manage their connections.
+pub trait Storage: Debug + Send + Sync {
+    /// Saves entries as iterator returns them in single transaction, which is rolled back if
+    /// iterator returns error. New entries will be inserted, entries with newer update time will
+    /// be replaced and previous version moved to revision history.
+    fn save_entries(
+        &self,
+        author: &str,
+        entries: &mut dyn Iterator<Item = DatabaseResult<EntryData>>,
+    ) -> DatabaseResult<SaveStats>;
+
+    /// Returns publication time of newest entry of given author.
+    fn last_published(&self, author: &str) -> DatabaseResult<Option<i64>>;
+
+    /// Returns data of entries of given author published in given interval.
+    fn published_between(
+        &self,
+        author: &str,
+        start_date: i64,
+        end_date: i64,
+    ) -> DatabaseResult<Vec<String>>;
+
+    /// Returns author and data of page of entries matching query, newest first. Entries of all
+    /// members are returned if query has no members.
+    fn feed_entries(&self, query: &FeedQuery) -> DatabaseResult<Vec<(String, String)>>;
+
+    fn entry_count(&self) -> DatabaseResult<usize>;
+
+    fn revision_count(&self) -> DatabaseResult<usize>;
+
+    /// Returns author and entry of up to `limit` entries with identifier greater than given one,
+    /// ordered by identifier.
+    fn entries_after(
+        &self,
+        last_id: &str,
+        limit: usize,
+    ) -> DatabaseResult<Vec<(String, EntryData)>>;
+
+    /// Returns up to `limit` revisions following given identifier and update time, ordered by
+    /// identifier and update time.
+    fn revisions_after(
+        &self,
+        last_id: &str,
+        last_updated: i64,
+        limit: usize,
+    ) -> DatabaseResult<Vec<RevisionData>>;
+
+    /// Inserts entries and revisions as they are in single transaction, existing entry or
+    /// revision is an error.
+    fn restore(
+        &self,
+        entries: &[(String, EntryData)],
+        revisions: &[RevisionData],
+    ) -> DatabaseResult<()>;
+
+    /// Writes consistent copy of database to given path, file must not exist.
+    fn backup(&self, _path: &str) -> DatabaseResult<()> {
+        Err(DatabaseError::unsupported("backup"))
+    }
+
+    /// Returns author and data of all entries referring to given issue as object or target,
+    /// oldest first.
+    fn issue_entries(&self, issue_key: &str) -> DatabaseResult<Vec<(String, String)>>;
+
+    /// Returns entries matching full-text query, best matches first.
+    fn search(&self, query: &SearchQuery) -> DatabaseResult<Vec<SearchResult>>;
+
+    /// Deletes entries published before given time together with their revisions, newest entry of
+    /// every member is kept. Returns number of deleted entries.
+    fn delete_published_before(&self, time: i64) -> DatabaseResult<usize>;
+}
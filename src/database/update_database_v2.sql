@@ -0,0 +1,10 @@
+ALTER TABLE entry ADD COLUMN updated INTEGER NOT NULL DEFAULT 0 ;
+
+UPDATE entry SET updated = published ;
+
+CREATE TABLE entry_revision (
+    id TEXT NOT NULL,
+    updated INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (id, updated)
+) WITHOUT ROWID ;
@@ -0,0 +1 @@
+CREATE INDEX entry_published ON entry(published) ;
@@ -0,0 +1,26 @@
+CREATE TABLE entry_document (
+    rowid INTEGER PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    application TEXT NOT NULL,
+    object_types TEXT NOT NULL,
+    title TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    content TEXT NOT NULL
+) ;
+
+CREATE VIRTUAL TABLE entry_search USING fts5 (
+    title,
+    summary,
+    content,
+    content = 'entry_document',
+    content_rowid = 'rowid',
+    tokenize = 'porter unicode61'
+) ;
+
+CREATE TRIGGER entry_document_insert AFTER INSERT ON entry_document BEGIN
+    INSERT INTO entry_search ( rowid, title, summary, content ) VALUES ( new.rowid, new.title, new.summary, new.content ) ;
+END ;
+
+CREATE TRIGGER entry_document_delete AFTER DELETE ON entry_document BEGIN
+    INSERT INTO entry_search ( entry_search, rowid, title, summary, content ) VALUES ( 'delete', old.rowid, old.title, old.summary, old.content ) ;
+END ;
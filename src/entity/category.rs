@@ -0,0 +1,31 @@
+/// Entry category, `term` identifies category within optional `scheme`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Category {
+    term: String,
+    #[serde(default)]
+    scheme: Option<String>,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+impl Category {
+    pub fn new(term: &str, scheme: Option<&String>, label: Option<&String>) -> Category {
+        Category {
+            term: term.into(),
+            scheme: scheme.cloned(),
+            label: label.cloned(),
+        }
+    }
+
+    pub fn term(&self) -> &str {
+        &self.term
+    }
+
+    pub fn scheme(&self) -> Option<&String> {
+        self.scheme.as_ref()
+    }
+
+    pub fn label(&self) -> Option<&String> {
+        self.label.as_ref()
+    }
+}
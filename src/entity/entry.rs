@@ -1,4 +1,11 @@
+use super::html::extract_content;
+use super::Category;
+use super::Content;
+use super::IssueKey;
+use super::Link;
 use super::Object;
+use super::ReplyContext;
+use super::Timestamp;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entry {
@@ -9,11 +16,23 @@ pub struct Entry {
     application: String,
     content: Option<String>,
     id: String,
-    published: String,
+    published: Timestamp,
     timezone_offset: String,
     title: String,
-    updated: String,
+    updated: Timestamp,
     verbs: Vec<String>,
+    #[serde(default)]
+    mentions: Vec<IssueKey>,
+    #[serde(default)]
+    links: Vec<Link>,
+    #[serde(default)]
+    categories: Vec<Category>,
+    #[serde(default)]
+    in_reply_to: Option<ReplyContext>,
+    #[serde(default)]
+    generator: Option<String>,
+    #[serde(default)]
+    icon: Option<String>,
 }
 
 impl Entry {
@@ -25,13 +44,13 @@ impl Entry {
         application: &str,
         content: Option<&String>,
         id: &str,
-        published: &str,
+        published: Timestamp,
         timezone_offset: &str,
         title: &str,
-        updated: &str,
+        updated: Timestamp,
         verbs: &[String],
     ) -> Entry {
-        Entry {
+        let mut entry = Entry {
             author,
             objects: objects.into(),
             target: target.cloned(),
@@ -39,12 +58,46 @@ impl Entry {
             application: application.into(),
             content: content.cloned(),
             id: id.into(),
-            published: published.into(),
+            published,
             timezone_offset: timezone_offset.into(),
             title: title.into(),
-            updated: updated.into(),
+            updated,
             verbs: verbs.into(),
-        }
+            mentions: Vec::with_capacity(0),
+            links: Vec::with_capacity(0),
+            categories: Vec::with_capacity(0),
+            in_reply_to: None,
+            generator: None,
+            icon: None,
+        };
+        entry.mentions = entry.find_mentions();
+
+        entry
+    }
+
+    pub fn with_links(mut self, links: &[Link]) -> Entry {
+        self.links = links.into();
+        self
+    }
+
+    pub fn with_categories(mut self, categories: &[Category]) -> Entry {
+        self.categories = categories.into();
+        self
+    }
+
+    pub fn with_in_reply_to(mut self, in_reply_to: Option<&ReplyContext>) -> Entry {
+        self.in_reply_to = in_reply_to.cloned();
+        self
+    }
+
+    pub fn with_generator(mut self, generator: Option<&String>) -> Entry {
+        self.generator = generator.cloned();
+        self
+    }
+
+    pub fn with_icon(mut self, icon: Option<&String>) -> Entry {
+        self.icon = icon.cloned();
+        self
     }
 
     pub fn author(&self) -> &Object {
@@ -71,12 +124,19 @@ impl Entry {
         self.content.as_ref()
     }
 
+    /// Returns content converted from HTML to plain text with links and issue keys.
+    pub fn content_text(&self) -> Option<Content> {
+        self.content
+            .as_ref()
+            .map(|content| extract_content(content))
+    }
+
     pub fn id(&self) -> &str {
         &self.id
     }
 
-    pub fn published(&self) -> &str {
-        &self.published
+    pub fn published(&self) -> Timestamp {
+        self.published
     }
 
     pub fn timezone_offset(&self) -> &str {
@@ -87,11 +147,105 @@ impl Entry {
         &self.title
     }
 
-    pub fn updated(&self) -> &str {
-        &self.updated
+    /// Returns title converted from HTML to plain text.
+    pub fn title_text(&self) -> String {
+        extract_content(&self.title).text().into()
+    }
+
+    pub fn updated(&self) -> Timestamp {
+        self.updated
     }
 
     pub fn verbs(&self) -> &[String] {
         &self.verbs
     }
+
+    /// Returns all entry links including alternate.
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Returns first link with given relation.
+    pub fn link(&self, rel: &str) -> Option<&Link> {
+        self.links.iter().find(|link| link.rel() == rel)
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    pub fn in_reply_to(&self) -> Option<&ReplyContext> {
+        self.in_reply_to.as_ref()
+    }
+
+    pub fn generator(&self) -> Option<&String> {
+        self.generator.as_ref()
+    }
+
+    pub fn icon(&self) -> Option<&String> {
+        self.icon.as_ref()
+    }
+
+    /// Returns keys of issues entry objects and target refer to.
+    pub fn issue_keys(&self) -> Vec<IssueKey> {
+        self.objects
+            .iter()
+            .chain(self.target.iter())
+            .filter_map(|o| o.issue_key())
+            .collect()
+    }
+
+    /// Returns keys of other issues mentioned in entry title or content. Mentions of entries
+    /// stored before they were recorded are found again.
+    pub fn mentions(&self) -> Vec<IssueKey> {
+        if self.mentions.is_empty() {
+            self.find_mentions()
+        } else {
+            self.mentions.clone()
+        }
+    }
+
+    /// Returns project keys of entry issues, mentioned issues are used if entry does not refer to
+    /// any issue directly.
+    pub fn projects(&self) -> Vec<String> {
+        let mut issue_keys = self.issue_keys();
+
+        if issue_keys.is_empty() {
+            issue_keys = self.mentions();
+        }
+
+        let mut result: Vec<String> = Vec::with_capacity(issue_keys.len());
+
+        for key in issue_keys {
+            if !result.iter().any(|project| project == key.project()) {
+                result.push(key.project().into());
+            }
+        }
+
+        result
+    }
+
+    fn find_mentions(&self) -> Vec<IssueKey> {
+        let own_keys = self.issue_keys();
+        let mut result: Vec<IssueKey> = extract_content(&self.title).issue_keys().into();
+
+        if let Some(content) = self.content_text() {
+            for key in content.issue_keys() {
+                if !result.contains(key) {
+                    result.push(key.clone());
+                }
+            }
+        }
+
+        result.retain(|key| !own_keys.contains(key));
+
+        result
+    }
+
+    /// Returns true if any entry object has unknown type or missing optional fields.
+    pub fn is_degraded(&self) -> bool {
+        self.author.is_degraded()
+            || self.objects.iter().any(|o| o.is_degraded())
+            || self.target.as_ref().map_or(false, |t| t.is_degraded())
+    }
 }
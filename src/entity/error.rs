@@ -8,19 +8,10 @@ pub type ObjectResult<T> = Result<T, ObjectError>;
 #[derive(Debug)]
 pub enum ObjectError {
     MissingObjectType,
-    WrongObjectType { object_type: String },
     ElementNotFound { element_name: String },
 }
 
 impl ObjectError {
-    pub fn wrong_object_type(object_type: &str) -> ObjectError {
-        warn!("Wrong object type: {}", object_type);
-
-        ObjectError::WrongObjectType {
-            object_type: object_type.into(),
-        }
-    }
-
     pub fn element_not_found(element_name: &str) -> ObjectError {
         warn!("Element not found: {}", element_name);
 
@@ -34,9 +25,6 @@ impl Display for ObjectError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self {
             ObjectError::MissingObjectType => write!(f, "Missing object type"),
-            ObjectError::WrongObjectType { ref object_type } => {
-                write!(f, "Wrong object type: `{}`", object_type)
-            }
             ObjectError::ElementNotFound { ref element_name } => {
                 write!(f, "Element `{}` not found", element_name)
             }
@@ -51,8 +39,10 @@ pub type EntryResult<T> = Result<T, EntryError>;
 #[derive(Debug)]
 pub enum EntryError {
     ElementNotFound { element_name: String },
+    InvalidTimestamp { element_name: String, value: String },
     XmlEventError { message: String },
     ReadObjectError { message: String },
+    NestedEntry { id: Option<String> },
 }
 
 impl EntryError {
@@ -64,6 +54,15 @@ impl EntryError {
         }
     }
 
+    pub fn invalid_timestamp(element_name: &str, value: &str) -> EntryError {
+        warn!("Invalid timestamp in {}: {}", element_name, value);
+
+        EntryError::InvalidTimestamp {
+            element_name: element_name.into(),
+            value: value.into(),
+        }
+    }
+
     pub fn xml_event_error<E>(error: E) -> EntryError
     where
         E: Error,
@@ -85,6 +84,12 @@ impl EntryError {
             message: format!("{}", error),
         }
     }
+
+    pub fn nested_entry(id: Option<&String>) -> EntryError {
+        warn!("Entry nested in entry {:?}", id);
+
+        EntryError::NestedEntry { id: id.cloned() }
+    }
 }
 
 impl Display for EntryError {
@@ -93,10 +98,18 @@ impl Display for EntryError {
             EntryError::ElementNotFound { ref element_name } => {
                 write!(f, "Element `{}` not found", element_name)
             }
+            EntryError::InvalidTimestamp {
+                ref element_name,
+                ref value,
+            } => write!(f, "Invalid timestamp `{}` in `{}`", value, element_name),
             EntryError::XmlEventError { ref message } => write!(f, "XML read error: `{}`", message),
             EntryError::ReadObjectError { ref message } => {
                 write!(f, "Read object error: `{}`", message)
             }
+            EntryError::NestedEntry { id: Some(ref id) } => {
+                write!(f, "Entry nested in entry `{}`", id)
+            }
+            EntryError::NestedEntry { id: None } => write!(f, "Entry nested in entry"),
         }
     }
 }
@@ -108,8 +121,11 @@ pub type FeedResult<T> = Result<T, FeedError>;
 #[derive(Debug)]
 pub enum FeedError {
     ElementNotFound { element_name: String },
+    InvalidTimestamp { element_name: String, value: String },
     XmlEventError { message: String },
     ReadEntryError { message: String },
+    JsonError { message: String },
+    IoError { message: String },
 }
 
 impl FeedError {
@@ -121,6 +137,15 @@ impl FeedError {
         }
     }
 
+    pub fn invalid_timestamp(element_name: &str, value: &str) -> FeedError {
+        warn!("Invalid timestamp in {}: {}", element_name, value);
+
+        FeedError::InvalidTimestamp {
+            element_name: element_name.into(),
+            value: value.into(),
+        }
+    }
+
     pub fn xml_event_error<E>(error: E) -> FeedError
     where
         E: Error,
@@ -142,6 +167,28 @@ impl FeedError {
             message: format!("{}", error),
         }
     }
+
+    pub fn json_error<E>(error: E) -> FeedError
+    where
+        E: Error,
+    {
+        warn!("JSON error: {}", error);
+
+        FeedError::JsonError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn io_error<E>(error: E) -> FeedError
+    where
+        E: Error,
+    {
+        warn!("IO error: {}", error);
+
+        FeedError::IoError {
+            message: format!("{}", error),
+        }
+    }
 }
 
 impl Display for FeedError {
@@ -150,12 +197,50 @@ impl Display for FeedError {
             FeedError::ElementNotFound { ref element_name } => {
                 write!(f, "Element `{}` not found", element_name)
             }
+            FeedError::InvalidTimestamp {
+                ref element_name,
+                ref value,
+            } => write!(f, "Invalid timestamp `{}` in `{}`", value, element_name),
             FeedError::XmlEventError { ref message } => write!(f, "XML read error: `{}`", message),
             FeedError::ReadEntryError { ref message } => {
                 write!(f, "Read entry error: `{}`", message)
             }
+            FeedError::JsonError { ref message } => write!(f, "JSON read error: `{}`", message),
+            FeedError::IoError { ref message } => write!(f, "IO error: `{}`", message),
         }
     }
 }
 
 impl Error for FeedError {}
+
+pub type WriteResult<T> = Result<T, WriteError>;
+
+#[derive(Debug)]
+pub enum WriteError {
+    XmlWriteError { message: String },
+}
+
+impl WriteError {
+    pub fn xml_write_error<E>(error: E) -> WriteError
+    where
+        E: Error,
+    {
+        warn!("XML write error: {}", error);
+
+        WriteError::XmlWriteError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            WriteError::XmlWriteError { ref message } => {
+                write!(f, "XML write error: `{}`", message)
+            }
+        }
+    }
+}
+
+impl Error for WriteError {}
@@ -1,11 +1,12 @@
 use super::Entry;
+use super::Timestamp;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Feed {
     id: String,
     title: String,
     timezone_offset: String,
-    updated: String,
+    updated: Timestamp,
     entries: Vec<Entry>,
 }
 
@@ -14,14 +15,14 @@ impl Feed {
         id: &str,
         title: &str,
         timezone_offset: &str,
-        updated: &str,
+        updated: Timestamp,
         entries: &[Entry],
     ) -> Feed {
         Feed {
             id: id.into(),
             title: title.into(),
             timezone_offset: timezone_offset.into(),
-            updated: updated.into(),
+            updated,
             entries: entries.into(),
         }
     }
@@ -38,8 +39,8 @@ impl Feed {
         &self.timezone_offset
     }
 
-    pub fn updated(&self) -> &str {
-        &self.updated
+    pub fn updated(&self) -> Timestamp {
+        self.updated
     }
 
     pub fn entries(&self) -> &[Entry] {
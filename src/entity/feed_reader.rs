@@ -0,0 +1,69 @@
+use std::io::Read;
+use xml::reader::Events;
+use xml::reader::XmlEvent;
+use xml::EventReader;
+
+use super::read::read_entry;
+use super::Entry;
+use super::EntryError;
+use super::EntryResult;
+
+/// Reads Atom feed entries one by one directly from input. Feed properties are skipped. Broken
+/// entry is reported as error and reading continues from next entry, malformed XML stops reading.
+pub struct FeedReader<R>
+where
+    R: Read,
+{
+    events: Events<R>,
+    finished: bool,
+}
+
+impl<R> FeedReader<R>
+where
+    R: Read,
+{
+    pub fn new(read: R) -> FeedReader<R> {
+        FeedReader {
+            events: EventReader::new(read).into_iter(),
+            finished: false,
+        }
+    }
+}
+
+impl<R> Iterator for FeedReader<R>
+where
+    R: Read,
+{
+    type Item = EntryResult<Entry>;
+
+    fn next(&mut self) -> Option<EntryResult<Entry>> {
+        if self.finished {
+            return None;
+        }
+
+        while let Some(result) = self.events.next() {
+            match result {
+                Ok(XmlEvent::StartElement { ref name, .. }) if name.local_name == "entry" => {
+                    let result = read_entry(&mut self.events);
+
+                    if let Err(EntryError::XmlEventError { .. }) = result {
+                        self.finished = true;
+                    }
+
+                    return Some(result);
+                }
+                Ok(XmlEvent::EndDocument) => break,
+                Ok(_) => {}
+                Err(err) => {
+                    self.finished = true;
+
+                    return Some(Err(EntryError::xml_event_error(err)));
+                }
+            }
+        }
+
+        self.finished = true;
+
+        None
+    }
+}
@@ -0,0 +1,357 @@
+use std::char;
+
+use super::IssueKey;
+
+const SUMMARY_LENGTH: usize = 100;
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+const SKIPPED_TAGS: &[&str] = &["script", "style", "head", "title"];
+
+/// Link found in HTML content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentLink {
+    text: String,
+    href: String,
+}
+
+impl ContentLink {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+}
+
+/// Plain text representation of HTML entry content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Content {
+    text: String,
+    links: Vec<ContentLink>,
+    issue_keys: Vec<IssueKey>,
+}
+
+impl Content {
+    /// Text without markup, block elements are separated with new lines.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn links(&self) -> &[ContentLink] {
+        &self.links
+    }
+
+    /// Distinct issue keys like `PROJ-123` in order of appearance.
+    pub fn issue_keys(&self) -> &[IssueKey] {
+        &self.issue_keys
+    }
+
+    /// Returns first line of text shortened to 100 characters.
+    pub fn summary(&self) -> String {
+        let line = self.text.lines().next().unwrap_or_default();
+
+        if line.chars().count() > SUMMARY_LENGTH {
+            let mut summary: String = line.chars().take(SUMMARY_LENGTH - 1).collect();
+            summary.push('…');
+
+            summary
+        } else {
+            line.into()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Tag {
+    name: String,
+    closing: bool,
+    href: Option<String>,
+}
+
+/// Extracts plain text, links and issue keys from HTML fragment. Parser is lenient: unclosed
+/// tags and unknown entities are kept as they are, contents of `script` and `style` are dropped.
+pub fn extract_content(html: &str) -> Content {
+    let mut text = String::with_capacity(html.len());
+    let mut links = Vec::new();
+    let mut link: Option<(String, usize)> = None;
+    let mut skipped: Option<String> = None;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            rest = match rest.find("-->") {
+                Some(index) => &rest[index + 3..],
+                None => "",
+            };
+
+            continue;
+        }
+
+        if rest.starts_with('<') {
+            if let Some((tag, length)) = parse_tag(rest) {
+                rest = &rest[length..];
+
+                if let Some(ref name) = skipped {
+                    if !(tag.closing && tag.name == *name) {
+                        continue;
+                    }
+                }
+
+                skipped = None;
+
+                if !tag.closing && SKIPPED_TAGS.contains(&tag.name.as_str()) {
+                    skipped = Some(tag.name);
+                } else if BLOCK_TAGS.contains(&tag.name.as_str()) {
+                    push_line_break(&mut text);
+                } else if tag.name == "a" {
+                    if tag.closing {
+                        if let Some((href, start)) = link.take() {
+                            links.push(ContentLink {
+                                text: collapse_whitespace(&text[start..]),
+                                href,
+                            });
+                        }
+                    } else if let Some(href) = tag.href {
+                        link = Some((href, text.len()));
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        let first = rest
+            .chars()
+            .next()
+            .map(|c| c.len_utf8())
+            .unwrap_or_default();
+        let end = rest[first..]
+            .find('<')
+            .map(|i| i + first)
+            .unwrap_or_else(|| rest.len());
+
+        if skipped.is_none() {
+            push_text(&mut text, &decode_entities(&rest[..end]));
+        }
+
+        rest = &rest[end..];
+    }
+
+    if let Some((href, start)) = link {
+        links.push(ContentLink {
+            text: collapse_whitespace(&text[start..]),
+            href,
+        });
+    }
+
+    let text = text
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let issue_keys = IssueKey::find_all(&text);
+
+    Content {
+        text,
+        links,
+        issue_keys,
+    }
+}
+
+/// Parses tag starting at beginning of text. Returns tag and its length in bytes or `None` if
+/// text does not start with tag.
+fn parse_tag(text: &str) -> Option<(Tag, usize)> {
+    let mut quote = None;
+    let mut end = None;
+
+    for (index, c) in text.char_indices().skip(1) {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => {
+                end = Some(index);
+                break;
+            }
+            None if c == '<' => return None,
+            None => {}
+        }
+    }
+
+    let end = end?;
+    let body = text[1..end].trim_end_matches('/').trim();
+    let (closing, body) = if body.starts_with('/') {
+        (true, &body[1..])
+    } else {
+        (false, body)
+    };
+    let name_end = body
+        .find(|c: char| c.is_whitespace())
+        .unwrap_or_else(|| body.len());
+    let name = body[..name_end].to_lowercase();
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '!') {
+        return None;
+    }
+
+    let href = if name == "a" && !closing {
+        find_attribute(&body[name_end..], "href")
+    } else {
+        None
+    };
+
+    Some((
+        Tag {
+            name,
+            closing,
+            href,
+        },
+        end + 1,
+    ))
+}
+
+fn find_attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes.trim_start();
+
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or_else(|| rest.len());
+        let attribute = rest[..name_end].to_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        if !rest.starts_with('=') {
+            continue;
+        }
+
+        rest = rest[1..].trim_start();
+
+        let (value, length) = match rest.chars().next() {
+            Some(q) if q == '"' || q == '\'' => match rest[1..].find(q) {
+                Some(index) => (&rest[1..index + 1], index + 2),
+                None => (&rest[1..], rest.len()),
+            },
+            _ => {
+                let index = rest
+                    .find(|c: char| c.is_whitespace())
+                    .unwrap_or_else(|| rest.len());
+
+                (&rest[..index], index)
+            }
+        };
+
+        if attribute == name {
+            return Some(decode_entities(value));
+        }
+
+        rest = rest[length..].trim_start();
+    }
+
+    None
+}
+
+/// Decodes character references and most common named entities.
+fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+
+    result
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if name.starts_with("#x") || name.starts_with("#X") {
+        return u32::from_str_radix(&name[2..], 16)
+            .ok()
+            .and_then(char::from_u32);
+    }
+
+    if name.starts_with('#') {
+        return name[1..].parse().ok().and_then(char::from_u32);
+    }
+
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        "ndash" => Some('–'),
+        "mdash" => Some('—'),
+        "hellip" => Some('…'),
+        _ => None,
+    }
+}
+
+/// Appends text collapsing whitespace to single spaces.
+fn push_text(result: &mut String, text: &str) {
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !result.is_empty() && !result.ends_with(' ') && !result.ends_with('\n') {
+                result.push(' ');
+            }
+        } else {
+            result.push(c);
+        }
+    }
+}
+
+fn push_line_break(result: &mut String) {
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
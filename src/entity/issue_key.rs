@@ -0,0 +1,116 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// Issue key like `PROJ-123`: project key of uppercase letters, digits and underscores starting
+/// with letter, dash and issue number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IssueKey {
+    key: String,
+    project: String,
+    number: u64,
+}
+
+impl IssueKey {
+    /// Parses whole text as issue key.
+    pub fn parse(text: &str) -> Option<IssueKey> {
+        let text = text.trim();
+        let index = text.rfind('-')?;
+        let project = &text[..index];
+        let number = &text[index + 1..];
+
+        if !is_project_key(project) || number.is_empty() {
+            return None;
+        }
+
+        if !number.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(IssueKey {
+            key: text.into(),
+            project: project.into(),
+            number: number.parse().ok()?,
+        })
+    }
+
+    /// Finds distinct issue keys in text in order of appearance.
+    pub fn find_all(text: &str) -> Vec<IssueKey> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result: Vec<IssueKey> = Vec::new();
+        let mut index = 0;
+
+        while index < chars.len() {
+            let start = index;
+            let is_word_start = start == 0 || !is_key_char(chars[start - 1]);
+
+            if !is_word_start || !chars[start].is_ascii_uppercase() {
+                index += 1;
+                continue;
+            }
+
+            while index < chars.len() && is_project_char(chars[index]) {
+                index += 1;
+            }
+
+            if index - start < 2 || index >= chars.len() || chars[index] != '-' {
+                continue;
+            }
+
+            let number_start = index + 1;
+            let mut number_end = number_start;
+
+            while number_end < chars.len() && chars[number_end].is_ascii_digit() {
+                number_end += 1;
+            }
+
+            let is_word_end = number_end == chars.len() || !is_key_char(chars[number_end]);
+
+            if number_end > number_start && is_word_end {
+                let key: String = chars[start..number_end].iter().collect();
+
+                if let Some(key) = IssueKey::parse(&key) {
+                    if !result.contains(&key) {
+                        result.push(key);
+                    }
+                }
+            }
+
+            index = number_end;
+        }
+
+        result
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+}
+
+impl Display for IssueKey {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.key)
+    }
+}
+
+fn is_project_key(text: &str) -> bool {
+    text.len() >= 2
+        && text.starts_with(|c: char| c.is_ascii_uppercase())
+        && text.chars().all(is_project_char)
+}
+
+fn is_project_char(c: char) -> bool {
+    c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
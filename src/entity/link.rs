@@ -0,0 +1,73 @@
+/// Entry link with relation, target and optional media type and title.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    rel: String,
+    href: String,
+    #[serde(default)]
+    media_type: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+impl Link {
+    pub fn new(rel: &str, href: &str, media_type: Option<&String>, title: Option<&String>) -> Link {
+        Link {
+            rel: rel.into(),
+            href: href.into(),
+            media_type: media_type.cloned(),
+            title: title.cloned(),
+        }
+    }
+
+    pub fn rel(&self) -> &str {
+        &self.rel
+    }
+
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+
+    pub fn media_type(&self) -> Option<&String> {
+        self.media_type.as_ref()
+    }
+
+    pub fn title(&self) -> Option<&String> {
+        self.title.as_ref()
+    }
+}
+
+/// Entry the activity replies to, as defined by Atom threading extension `in-reply-to`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplyContext {
+    reference: String,
+    #[serde(default)]
+    href: Option<String>,
+    #[serde(default)]
+    media_type: Option<String>,
+}
+
+impl ReplyContext {
+    pub fn new(
+        reference: &str,
+        href: Option<&String>,
+        media_type: Option<&String>,
+    ) -> ReplyContext {
+        ReplyContext {
+            reference: reference.into(),
+            href: href.cloned(),
+            media_type: media_type.cloned(),
+        }
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    pub fn href(&self) -> Option<&String> {
+        self.href.as_ref()
+    }
+
+    pub fn media_type(&self) -> Option<&String> {
+        self.media_type.as_ref()
+    }
+}
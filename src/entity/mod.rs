@@ -1,12 +1,28 @@
+use chrono::DateTime;
+use chrono::FixedOffset;
+use serde_json;
+use std::io::BufRead;
+use std::io::BufReader;
 use std::io::Read;
 use xml::EventReader;
 
+mod category;
 mod entry;
 mod error;
 mod feed;
+mod feed_reader;
+mod html;
+mod issue_key;
+mod link;
 mod object;
 mod read;
+mod read_json;
+#[cfg(test)]
+mod tests;
+mod write;
+mod write_json;
 
+pub use self::category::Category;
 pub use self::entry::Entry;
 pub use self::error::EntryError;
 pub use self::error::EntryResult;
@@ -14,9 +30,53 @@ pub use self::error::FeedError;
 pub use self::error::FeedResult;
 pub use self::error::ObjectError;
 pub use self::error::ObjectResult;
+pub use self::error::WriteError;
+pub use self::error::WriteResult;
 pub use self::feed::Feed;
+pub use self::feed_reader::FeedReader;
+pub use self::html::Content;
+pub use self::issue_key::IssueKey;
+pub use self::link::Link;
+pub use self::link::ReplyContext;
 pub use self::object::Object;
 pub use self::read::read_feed;
+pub use self::read_json::read_json_entries;
+pub use self::read_json::read_json_feed;
+pub use self::write::write_feed;
+pub use self::write_json::write_json_feed;
+
+/// Time with offset from UTC as defined in RFC 3339.
+pub type Timestamp = DateTime<FixedOffset>;
+
+/// Input feed format.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum FeedFormat {
+    /// Detect format from feed content.
+    Auto,
+    /// Atom feed with activity streams extension.
+    Atom,
+    /// W3C Activity Streams 2.0 JSON.
+    ActivityStreams2,
+}
+
+impl FeedFormat {
+    /// Returns format of given media type, e.g. value of `Content-Type` header. Parameters like
+    /// charset are ignored.
+    pub fn from_media_type(media_type: &str) -> Option<FeedFormat> {
+        let essence = media_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+
+        match essence.as_str() {
+            "application/activity+json" | "application/json" => Some(FeedFormat::ActivityStreams2),
+            "application/atom+xml" => Some(FeedFormat::Atom),
+            _ => None,
+        }
+    }
+}
 
 pub fn read<R>(read: R) -> FeedResult<Feed>
 where
@@ -26,3 +86,67 @@ where
 
     read_feed(&mut it)
 }
+
+pub fn read_json<R>(read: R) -> FeedResult<Feed>
+where
+    R: Read,
+{
+    let value = serde_json::from_reader(read).map_err(FeedError::json_error)?;
+
+    read_json_feed(&value)
+}
+
+/// Reads feed entries one by one in given format. Atom entries are parsed while input is read,
+/// JSON document is loaded completely. Automatic detection checks first non-whitespace
+/// character, which may follow whitespace of several reads: JSON documents start with `{`,
+/// otherwise feed will be read as Atom.
+pub fn read_entries<'a, R>(
+    format: FeedFormat,
+    input: R,
+) -> FeedResult<Box<dyn Iterator<Item = EntryResult<Entry>> + 'a>>
+where
+    R: Read + 'a,
+{
+    let mut reader = BufReader::new(input);
+    let is_json = match format {
+        FeedFormat::Atom => false,
+        FeedFormat::ActivityStreams2 => true,
+        FeedFormat::Auto => starts_with_object(&mut reader)?,
+    };
+
+    if is_json {
+        let value: serde_json::Value =
+            serde_json::from_reader(reader).map_err(FeedError::json_error)?;
+
+        Ok(Box::new(read_json_entries(&value)?.into_iter()))
+    } else {
+        Ok(Box::new(FeedReader::new(reader)))
+    }
+}
+
+/// Skips leading whitespace and checks whether input continues with JSON object.
+fn starts_with_object<R>(reader: &mut R) -> FeedResult<bool>
+where
+    R: BufRead,
+{
+    loop {
+        let (whitespace, next) = {
+            let buffer = reader.fill_buf().map_err(FeedError::io_error)?;
+
+            if buffer.is_empty() {
+                return Ok(false);
+            }
+
+            match buffer.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(position) => (position, Some(buffer[position])),
+                None => (buffer.len(), None),
+            }
+        };
+
+        reader.consume(whitespace);
+
+        if let Some(next) = next {
+            return Ok(next == b'{');
+        }
+    }
+}
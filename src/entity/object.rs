@@ -1,7 +1,10 @@
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
 
+use super::IssueKey;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Object {
     Comment {
@@ -15,9 +18,11 @@ pub enum Object {
     },
     Person {
         name: String,
-        email: String,
+        #[serde(default)]
+        email: Option<String>,
         uri: String,
-        photo: String,
+        #[serde(default)]
+        photo: Option<String>,
         username: String,
     },
     Changeset {
@@ -30,6 +35,8 @@ pub enum Object {
         title: String,
         summary: String,
         alternate: String,
+        #[serde(default)]
+        key: Option<IssueKey>,
     },
     Repository {
         id: String,
@@ -52,6 +59,46 @@ pub enum Object {
         title: String,
         alternate: String,
     },
+    Attachment {
+        id: String,
+        title: String,
+        alternate: String,
+    },
+    Sprint {
+        id: String,
+        title: String,
+        alternate: String,
+    },
+    Board {
+        id: String,
+        title: String,
+        alternate: String,
+    },
+    Commit {
+        id: String,
+        title: String,
+        alternate: String,
+    },
+    Build {
+        id: String,
+        title: String,
+        alternate: String,
+    },
+    PullRequest {
+        id: String,
+        title: String,
+        summary: String,
+        alternate: String,
+    },
+    BlogPost {
+        id: String,
+        title: String,
+        alternate: String,
+    },
+    Unknown {
+        object_type: String,
+        properties: HashMap<String, String>,
+    },
 }
 
 impl Object {
@@ -70,12 +117,18 @@ impl Object {
         }
     }
 
-    pub fn person(name: &str, email: &str, uri: &str, photo: &str, username: &str) -> Object {
+    pub fn person(
+        name: &str,
+        email: Option<&str>,
+        uri: &str,
+        photo: Option<&str>,
+        username: &str,
+    ) -> Object {
         Object::Person {
             name: name.into(),
-            email: email.into(),
+            email: email.map(String::from),
             uri: uri.into(),
-            photo: photo.into(),
+            photo: photo.map(String::from),
             username: username.into(),
         }
     }
@@ -88,12 +141,14 @@ impl Object {
         }
     }
 
+    /// Creates issue, issue key is parsed from title or from last segment of alternate link.
     pub fn issue(id: &str, title: &str, summary: &str, alternate: &str) -> Object {
         Object::Issue {
             id: id.into(),
             title: title.into(),
             summary: summary.into(),
             alternate: alternate.into(),
+            key: parse_issue_key(title, alternate),
         }
     }
 
@@ -129,6 +184,194 @@ impl Object {
             alternate: alternate.into(),
         }
     }
+
+    pub fn attachment(id: &str, title: &str, alternate: &str) -> Object {
+        Object::Attachment {
+            id: id.into(),
+            title: title.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn sprint(id: &str, title: &str, alternate: &str) -> Object {
+        Object::Sprint {
+            id: id.into(),
+            title: title.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn board(id: &str, title: &str, alternate: &str) -> Object {
+        Object::Board {
+            id: id.into(),
+            title: title.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn commit(id: &str, title: &str, alternate: &str) -> Object {
+        Object::Commit {
+            id: id.into(),
+            title: title.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn build(id: &str, title: &str, alternate: &str) -> Object {
+        Object::Build {
+            id: id.into(),
+            title: title.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn pull_request(id: &str, title: &str, summary: &str, alternate: &str) -> Object {
+        Object::PullRequest {
+            id: id.into(),
+            title: title.into(),
+            summary: summary.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn blog_post(id: &str, title: &str, alternate: &str) -> Object {
+        Object::BlogPost {
+            id: id.into(),
+            title: title.into(),
+            alternate: alternate.into(),
+        }
+    }
+
+    pub fn unknown(object_type: &str, properties: &HashMap<String, String>) -> Object {
+        Object::Unknown {
+            object_type: object_type.into(),
+            properties: properties.clone(),
+        }
+    }
+
+    /// Returns object type URI as used in activity streams.
+    pub fn object_type(&self) -> &str {
+        match self {
+            Object::Comment { .. } => "http://activitystrea.ms/schema/1.0/comment",
+            Object::File { .. } => "http://activitystrea.ms/schema/1.0/file",
+            Object::Person { .. } => "http://activitystrea.ms/schema/1.0/person",
+            Object::Changeset { .. } => "http://streams.atlassian.com/syndication/types/changeset",
+            Object::Issue { .. } => "http://streams.atlassian.com/syndication/types/issue",
+            Object::Repository { .. } => {
+                "http://streams.atlassian.com/syndication/types/repository"
+            }
+            Object::Review { .. } => "http://streams.atlassian.com/syndication/types/review",
+            Object::Page { .. } => "http://streams.atlassian.com/syndication/types/page",
+            Object::Space { .. } => "http://streams.atlassian.com/syndication/types/space",
+            Object::Attachment { .. } => {
+                "http://streams.atlassian.com/syndication/types/attachment"
+            }
+            Object::Sprint { .. } => "http://streams.atlassian.com/syndication/types/sprint",
+            Object::Board { .. } => "http://streams.atlassian.com/syndication/types/board",
+            Object::Commit { .. } => "http://streams.atlassian.com/syndication/types/commit",
+            Object::Build { .. } => "http://streams.atlassian.com/syndication/types/build",
+            Object::PullRequest { .. } => {
+                "http://streams.atlassian.com/syndication/types/pull-request"
+            }
+            Object::BlogPost { .. } => "http://activitystrea.ms/schema/1.0/article",
+            Object::Unknown {
+                ref object_type, ..
+            } => object_type,
+        }
+    }
+
+    /// Returns object identifier, person identified by URI.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Object::Comment { ref id, .. }
+            | Object::File { ref id, .. }
+            | Object::Changeset { ref id, .. }
+            | Object::Issue { ref id, .. }
+            | Object::Repository { ref id, .. }
+            | Object::Review { ref id, .. }
+            | Object::Page { ref id, .. }
+            | Object::Space { ref id, .. }
+            | Object::Attachment { ref id, .. }
+            | Object::Sprint { ref id, .. }
+            | Object::Board { ref id, .. }
+            | Object::Commit { ref id, .. }
+            | Object::Build { ref id, .. }
+            | Object::PullRequest { ref id, .. }
+            | Object::BlogPost { ref id, .. } => Some(id),
+            Object::Person { ref uri, .. } => Some(uri),
+            Object::Unknown { ref properties, .. } => properties.get("id").map(|id| id.as_str()),
+        }
+    }
+
+    /// Returns user name of person.
+    pub fn username(&self) -> Option<&str> {
+        match self {
+            Object::Person { ref username, .. } => Some(username),
+            _ => None,
+        }
+    }
+
+    pub fn alternate(&self) -> Option<&str> {
+        match self {
+            Object::Comment { ref alternate, .. }
+            | Object::File { ref alternate, .. }
+            | Object::Changeset { ref alternate, .. }
+            | Object::Issue { ref alternate, .. }
+            | Object::Repository { ref alternate, .. }
+            | Object::Review { ref alternate, .. }
+            | Object::Page { ref alternate, .. }
+            | Object::Space { ref alternate, .. }
+            | Object::Attachment { ref alternate, .. }
+            | Object::Sprint { ref alternate, .. }
+            | Object::Board { ref alternate, .. }
+            | Object::Commit { ref alternate, .. }
+            | Object::Build { ref alternate, .. }
+            | Object::PullRequest { ref alternate, .. }
+            | Object::BlogPost { ref alternate, .. } => Some(alternate),
+            Object::Person { ref uri, .. } => Some(uri),
+            Object::Unknown { ref properties, .. } => properties
+                .get("alternate")
+                .map(|alternate| alternate.as_str()),
+        }
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        match self {
+            Object::Issue { ref summary, .. }
+            | Object::Review { ref summary, .. }
+            | Object::PullRequest { ref summary, .. } => Some(summary),
+            Object::Unknown { ref properties, .. } => {
+                properties.get("summary").map(|summary| summary.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns key of issue, issues stored before keys were parsed get it from title or link.
+    pub fn issue_key(&self) -> Option<IssueKey> {
+        match self {
+            Object::Issue {
+                ref key,
+                ref title,
+                ref alternate,
+                ..
+            } => key.clone().or_else(|| parse_issue_key(title, alternate)),
+            _ => None,
+        }
+    }
+
+    /// Returns true if object type is unknown or some optional fields are missing.
+    pub fn is_degraded(&self) -> bool {
+        match self {
+            Object::Person {
+                ref email,
+                ref photo,
+                ..
+            } => email.is_none() || photo.is_none(),
+            Object::Unknown { .. } => true,
+            _ => false,
+        }
+    }
 }
 
 impl Display for Object {
@@ -143,6 +386,25 @@ impl Display for Object {
             Object::Review { ref title, .. } => write!(f, "{}", title),
             Object::Page { ref title, .. } => write!(f, "{}", title),
             Object::Space { ref title, .. } => write!(f, "{}", title),
+            Object::Attachment { ref title, .. } => write!(f, "{}", title),
+            Object::Sprint { ref title, .. } => write!(f, "{}", title),
+            Object::Board { ref title, .. } => write!(f, "{}", title),
+            Object::Commit { ref title, .. } => write!(f, "{}", title),
+            Object::Build { ref title, .. } => write!(f, "{}", title),
+            Object::PullRequest { ref title, .. } => write!(f, "{}", title),
+            Object::BlogPost { ref title, .. } => write!(f, "{}", title),
+            Object::Unknown {
+                ref object_type,
+                ref properties,
+            } => match properties.get("title") {
+                Some(title) => write!(f, "{}", title),
+                None => write!(f, "{}", object_type),
+            },
         }
     }
 }
+
+/// Parses issue key from issue title or last segment of its link.
+fn parse_issue_key(title: &str, alternate: &str) -> Option<IssueKey> {
+    IssueKey::parse(title).or_else(|| alternate.rsplit('/').next().and_then(IssueKey::parse))
+}
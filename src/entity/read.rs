@@ -1,18 +1,26 @@
+use chrono::DateTime;
 use std::collections::HashMap;
+use xml::attribute::OwnedAttribute;
 use xml::reader::Result as XmlResult;
 use xml::reader::XmlEvent;
 
+use super::Category;
 use super::Entry;
 use super::EntryError;
 use super::EntryResult;
 use super::Feed;
 use super::FeedError;
 use super::FeedResult;
+use super::Link;
 use super::Object;
 use super::ObjectError;
 use super::ObjectResult;
+use super::ReplyContext;
+use super::Timestamp;
 
-pub fn read_map<I>(element_name: &str, it: &mut I) -> HashMap<String, String>
+/// Reads text and links of element children until element end. XML errors are returned, so
+/// truncated input is not mistaken for incomplete object.
+pub fn read_map<I>(element_name: &str, it: &mut I) -> XmlResult<HashMap<String, String>>
 where
     I: Iterator<Item = XmlResult<XmlEvent>>,
 {
@@ -47,12 +55,12 @@ where
             Err(err) => {
                 error!("Failed to read next event: {}", err);
 
-                break;
+                return Err(err);
             }
         }
     }
 
-    properties
+    Ok(properties)
 }
 
 fn read_object(properties: &HashMap<String, String>) -> ObjectResult<Object> {
@@ -72,7 +80,22 @@ fn read_object(properties: &HashMap<String, String>) -> ObjectResult<Object> {
         Some("http://streams.atlassian.com/syndication/types/review") => read_review(properties),
         Some("http://streams.atlassian.com/syndication/types/page") => read_page(properties),
         Some("http://streams.atlassian.com/syndication/types/space") => read_space(properties),
-        Some(object_type) => Err(ObjectError::wrong_object_type(object_type)),
+        Some("http://streams.atlassian.com/syndication/types/attachment") => {
+            read_attachment(properties)
+        }
+        Some("http://streams.atlassian.com/syndication/types/sprint") => read_sprint(properties),
+        Some("http://streams.atlassian.com/syndication/types/board") => read_board(properties),
+        Some("http://streams.atlassian.com/syndication/types/commit") => read_commit(properties),
+        Some("http://streams.atlassian.com/syndication/types/build") => read_build(properties),
+        Some("http://streams.atlassian.com/syndication/types/pull-request") => {
+            read_pull_request(properties)
+        }
+        Some("http://activitystrea.ms/schema/1.0/article") => read_blog_post(properties),
+        Some(object_type) => {
+            warn!("Unknown object type: {}", object_type);
+
+            Ok(Object::unknown(object_type, properties))
+        }
         None => Err(ObjectError::MissingObjectType),
     }
 }
@@ -106,15 +129,11 @@ fn read_person(properties: &HashMap<String, String>) -> ObjectResult<Object> {
     let name = properties
         .get("name")
         .ok_or_else(|| ObjectError::element_not_found("name"))?;
-    let email = properties
-        .get("email")
-        .ok_or_else(|| ObjectError::element_not_found("email"))?;
+    let email = properties.get("email").map(String::as_str);
     let uri = properties
         .get("uri")
         .ok_or_else(|| ObjectError::element_not_found("uri"))?;
-    let photo = properties
-        .get("photo")
-        .ok_or_else(|| ObjectError::element_not_found("photo"))?;
+    let photo = properties.get("photo").map(String::as_str);
     let username = properties
         .get("username")
         .ok_or_else(|| ObjectError::element_not_found("username"))?;
@@ -212,6 +231,107 @@ fn read_space(properties: &HashMap<String, String>) -> ObjectResult<Object> {
     Ok(Object::space(id, title, alternate))
 }
 
+fn read_attachment(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::attachment(id, title, alternate))
+}
+
+fn read_sprint(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::sprint(id, title, alternate))
+}
+
+fn read_board(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::board(id, title, alternate))
+}
+
+fn read_commit(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::commit(id, title, alternate))
+}
+
+fn read_build(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::build(id, title, alternate))
+}
+
+fn read_pull_request(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let summary = properties
+        .get("summary")
+        .ok_or_else(|| ObjectError::element_not_found("summary"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::pull_request(id, title, summary, alternate))
+}
+
+fn read_blog_post(properties: &HashMap<String, String>) -> ObjectResult<Object> {
+    let id = properties
+        .get("id")
+        .ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = properties
+        .get("title")
+        .ok_or_else(|| ObjectError::element_not_found("title"))?;
+    let alternate = properties
+        .get("alternate")
+        .ok_or_else(|| ObjectError::element_not_found("alternate"))?;
+
+    Ok(Object::blog_post(id, title, alternate))
+}
+
 pub fn read_entry<I>(it: &mut I) -> EntryResult<Entry>
 where
     I: Iterator<Item = XmlResult<XmlEvent>>,
@@ -222,6 +342,9 @@ where
     let mut objects: Vec<Object> = Vec::with_capacity(0);
     let mut target: Option<Object> = None;
     let mut verbs: Vec<String> = Vec::with_capacity(1);
+    let mut links: Vec<Link> = Vec::with_capacity(2);
+    let mut categories: Vec<Category> = Vec::with_capacity(0);
+    let mut in_reply_to: Option<ReplyContext> = None;
     let mut properties = HashMap::with_capacity(8);
     let mut element_name = "entry".into();
     let mut depth = 1;
@@ -233,27 +356,32 @@ where
 
         match event {
             XmlEvent::StartElement { ref name, .. } if name.local_name == "entry" => {
-                element_name = name.local_name.clone();
-                depth = 1;
+                // Rest of parent entry is skipped, so reading continues with following entry.
+                skip_element(depth + 1, it).map_err(EntryError::xml_event_error)?;
+
+                return Err(EntryError::nested_entry(properties.get("id")));
             }
             XmlEvent::StartElement { ref name, .. } if name.local_name == "author" => {
                 debug!("Reading author");
 
-                let properties = read_map(&name.local_name, it);
+                let properties =
+                    read_map(&name.local_name, it).map_err(EntryError::xml_event_error)?;
 
                 author = Some(read_object(&properties).map_err(EntryError::read_object_error)?);
             }
             XmlEvent::StartElement { ref name, .. } if name.local_name == "object" => {
                 debug!("Reading object");
 
-                let properties = read_map(&name.local_name, it);
+                let properties =
+                    read_map(&name.local_name, it).map_err(EntryError::xml_event_error)?;
 
                 objects.push(read_object(&properties).map_err(EntryError::read_object_error)?);
             }
             XmlEvent::StartElement { ref name, .. } if name.local_name == "target" => {
                 debug!("Reading target");
 
-                let properties = read_map(&name.local_name, it);
+                let properties =
+                    read_map(&name.local_name, it).map_err(EntryError::xml_event_error)?;
 
                 target = Some(read_object(&properties).map_err(EntryError::read_object_error)?);
             }
@@ -265,11 +393,83 @@ where
             {
                 debug!("Reading link");
 
-                let rel = attributes.iter().find(|e| e.name.local_name == "rel");
-                let href = attributes.iter().find(|e| e.name.local_name == "href");
+                let attributes = read_attributes(attributes);
 
-                if let (Some(rel), Some(href)) = (rel, href) {
-                    properties.insert(rel.value.clone(), href.value.clone());
+                if let Some(href) = attributes.get("href") {
+                    let rel = attributes
+                        .get("rel")
+                        .map(|rel| rel.as_str())
+                        .unwrap_or("alternate");
+
+                    // Last explicit alternate link is entry link, other links are kept only in
+                    // list of links.
+                    if attributes.get("rel").map(String::as_str) == Some("alternate") {
+                        properties.insert("alternate".into(), href.clone());
+                    }
+
+                    links.push(Link::new(
+                        rel,
+                        href,
+                        attributes.get("type"),
+                        attributes.get("title"),
+                    ));
+                }
+
+                element_name = name.local_name.clone();
+                depth += 1;
+            }
+            XmlEvent::StartElement {
+                ref name,
+                ref attributes,
+                ..
+            } if depth == 1 && name.local_name == "category" =>
+            {
+                debug!("Reading category");
+
+                let attributes = read_attributes(attributes);
+
+                if let Some(term) = attributes.get("term") {
+                    categories.push(Category::new(
+                        term,
+                        attributes.get("scheme"),
+                        attributes.get("label"),
+                    ));
+                }
+
+                element_name = name.local_name.clone();
+                depth += 1;
+            }
+            XmlEvent::StartElement {
+                ref name,
+                ref attributes,
+                ..
+            } if depth == 1 && name.local_name == "in-reply-to" =>
+            {
+                debug!("Reading reply context");
+
+                let attributes = read_attributes(attributes);
+
+                if let Some(reference) = attributes.get("ref") {
+                    in_reply_to = Some(ReplyContext::new(
+                        reference,
+                        attributes.get("href"),
+                        attributes.get("type"),
+                    ));
+                }
+
+                element_name = name.local_name.clone();
+                depth += 1;
+            }
+            XmlEvent::StartElement {
+                ref name,
+                ref attributes,
+                ..
+            } if depth == 1 && name.local_name == "generator" =>
+            {
+                let attributes = read_attributes(attributes);
+
+                if let Some(uri) = attributes.get("uri") {
+                    properties.insert("generator".into(), uri.clone());
                 }
 
                 element_name = name.local_name.clone();
@@ -294,7 +494,9 @@ where
                 debug!("Text: {}", text);
 
                 if depth == 2 {
-                    if element_name == "verb" {
+                    if element_name == "generator" {
+                        properties.entry(element_name.clone()).or_insert(text);
+                    } else if element_name == "verb" {
                         verbs.push(text);
                     } else {
                         properties.insert(element_name.clone(), text);
@@ -321,6 +523,8 @@ where
     let published = properties
         .get("published")
         .ok_or_else(|| EntryError::element_not_found("published"))?;
+    let published = parse_timestamp(published)
+        .ok_or_else(|| EntryError::invalid_timestamp("published", published))?;
     let timezone_offset = properties
         .get("timezone-offset")
         .ok_or_else(|| EntryError::element_not_found("timezone-offset"))?;
@@ -330,6 +534,16 @@ where
     let updated = properties
         .get("updated")
         .ok_or_else(|| EntryError::element_not_found("updated"))?;
+    let updated = parse_timestamp(updated)
+        .ok_or_else(|| EntryError::invalid_timestamp("updated", updated))?;
+
+    let generator = properties.get("generator");
+    let icon = properties.get("icon").cloned().or_else(|| {
+        links
+            .iter()
+            .find(|link| link.rel() == "icon" || link.rel().ends_with("/icon"))
+            .map(|link| link.href().into())
+    });
 
     debug!("Entry complete");
 
@@ -346,7 +560,42 @@ where
         title,
         updated,
         &verbs,
-    ))
+    )
+    .with_links(&links)
+    .with_categories(&categories)
+    .with_in_reply_to(in_reply_to.as_ref())
+    .with_generator(generator)
+    .with_icon(icon.as_ref()))
+}
+
+/// Skips events until element with given depth of open elements is closed.
+fn skip_element<I>(mut depth: usize, it: &mut I) -> XmlResult<()>
+where
+    I: Iterator<Item = XmlResult<XmlEvent>>,
+{
+    for result in it.by_ref() {
+        match result? {
+            XmlEvent::StartElement { .. } => depth += 1,
+            XmlEvent::EndElement { .. } => {
+                depth -= 1;
+
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns attribute values by local name.
+fn read_attributes(attributes: &[OwnedAttribute]) -> HashMap<String, String> {
+    attributes
+        .iter()
+        .map(|a| (a.name.local_name.clone(), a.value.clone()))
+        .collect()
 }
 
 pub fn read_feed<I>(it: &mut I) -> FeedResult<Feed>
@@ -419,6 +668,16 @@ where
 
     debug!("Feed properties = {:?}", properties);
 
+    let n_degraded = entries.iter().filter(|e| e.is_degraded()).count();
+
+    if n_degraded > 0 {
+        warn!(
+            "Feed contains {} degraded entries of {}",
+            n_degraded,
+            entries.len()
+        );
+    }
+
     let id = properties
         .get("id")
         .ok_or_else(|| FeedError::element_not_found("id"))?;
@@ -431,8 +690,15 @@ where
     let updated = properties
         .get("updated")
         .ok_or_else(|| FeedError::element_not_found("updated"))?;
+    let updated =
+        parse_timestamp(updated).ok_or_else(|| FeedError::invalid_timestamp("updated", updated))?;
 
     debug!("Feed complete");
 
     Ok(Feed::new(id, title, timezone_offset, updated, &entries))
 }
+
+/// Parses RFC 3339 time, fractional seconds and offset from UTC are supported.
+pub fn parse_timestamp(value: &str) -> Option<Timestamp> {
+    DateTime::parse_from_rfc3339(value.trim()).ok()
+}
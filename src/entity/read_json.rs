@@ -0,0 +1,306 @@
+use serde_json::Map;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::read::parse_timestamp;
+use super::Category;
+use super::Entry;
+use super::EntryError;
+use super::EntryResult;
+use super::Feed;
+use super::FeedError;
+use super::FeedResult;
+use super::Link;
+use super::Object;
+use super::ObjectError;
+use super::ObjectResult;
+use super::ReplyContext;
+use super::Timestamp;
+
+const VERB_PREFIX: &str = "http://activitystrea.ms/schema/1.0/";
+const DEFAULT_APPLICATION: &str = "activitystreams";
+const UTC_OFFSET: &str = "+0000";
+
+/// Reads feed from Activity Streams 2.0 collection. Both `orderedItems` and `items` are
+/// supported, single activity will be read as feed with one entry.
+pub fn read_json_feed(value: &Value) -> FeedResult<Feed> {
+    debug!("Reading JSON feed");
+
+    let collection = value
+        .as_object()
+        .ok_or_else(|| FeedError::element_not_found("collection"))?;
+    let items = read_items(value)?;
+    let mut entries = Vec::with_capacity(items.len());
+
+    for item in items {
+        entries.push(read_json_entry(item).map_err(FeedError::read_entry_error)?);
+    }
+
+    let id = get_string(collection, "id").unwrap_or_default();
+    let title = get_string(collection, "name")
+        .or_else(|| get_string(collection, "summary"))
+        .unwrap_or_default();
+    let updated = match get_string(collection, "updated") {
+        Some(updated) => Some(
+            parse_timestamp(&updated)
+                .ok_or_else(|| FeedError::invalid_timestamp("updated", &updated))?,
+        ),
+        None => entries.iter().map(|e| e.updated()).max(),
+    }
+    .ok_or_else(|| FeedError::element_not_found("updated"))?;
+
+    debug!("JSON feed complete");
+
+    Ok(Feed::new(&id, &title, UTC_OFFSET, updated, &entries))
+}
+
+/// Reads every collection item separately, so broken items can be skipped.
+pub fn read_json_entries(value: &Value) -> FeedResult<Vec<EntryResult<Entry>>> {
+    let items = read_items(value)?;
+
+    Ok(items.into_iter().map(read_json_entry).collect())
+}
+
+fn read_items(value: &Value) -> FeedResult<Vec<&Value>> {
+    let collection = value
+        .as_object()
+        .ok_or_else(|| FeedError::element_not_found("collection"))?;
+
+    match collection
+        .get("orderedItems")
+        .or_else(|| collection.get("items"))
+    {
+        Some(Value::Array(items)) => Ok(items.iter().collect()),
+        Some(_) => Err(FeedError::element_not_found("items")),
+        None if collection.contains_key("actor") => Ok(vec![value]),
+        None => Ok(Vec::with_capacity(0)),
+    }
+}
+
+pub fn read_json_entry(value: &Value) -> EntryResult<Entry> {
+    debug!("Reading JSON entry");
+
+    let activity = value
+        .as_object()
+        .ok_or_else(|| EntryError::element_not_found("activity"))?;
+    let activity_type =
+        get_string(activity, "type").ok_or_else(|| EntryError::element_not_found("type"))?;
+    let author = activity
+        .get("actor")
+        .ok_or_else(|| EntryError::element_not_found("actor"))
+        .and_then(|actor| read_json_object(actor).map_err(EntryError::read_object_error))?;
+    let objects = match activity.get("object") {
+        Some(Value::Array(objects)) => objects
+            .iter()
+            .map(read_json_object)
+            .collect::<ObjectResult<Vec<_>>>()
+            .map_err(EntryError::read_object_error)?,
+        Some(object) => vec![read_json_object(object).map_err(EntryError::read_object_error)?],
+        None => Vec::with_capacity(0),
+    };
+    let target = match activity.get("target") {
+        Some(target) => Some(read_json_object(target).map_err(EntryError::read_object_error)?),
+        None => None,
+    };
+    let id = get_string(activity, "id").ok_or_else(|| EntryError::element_not_found("id"))?;
+    let alternate = get_link(activity, "url").unwrap_or_else(|| id.clone());
+    let application = match activity.get("generator") {
+        Some(Value::Object(generator)) => get_string(generator, "name"),
+        Some(Value::String(generator)) => Some(generator.clone()),
+        _ => None,
+    }
+    .unwrap_or_else(|| DEFAULT_APPLICATION.into());
+    let generator = match activity.get("generator") {
+        Some(Value::Object(generator)) => get_string(generator, "id"),
+        _ => None,
+    };
+    let content = get_string(activity, "content");
+    let published = get_string(activity, "published")
+        .ok_or_else(|| EntryError::element_not_found("published"))?;
+    let published = read_timestamp(&published, "published")?;
+    let updated = match get_string(activity, "updated") {
+        Some(updated) => read_timestamp(&updated, "updated")?,
+        None => published,
+    };
+    let timezone_offset = timezone_offset(&published);
+    let title = get_string(activity, "name")
+        .or_else(|| get_string(activity, "summary"))
+        .unwrap_or_else(|| activity_type.clone());
+    let verbs = vec![activity_verb(&activity_type)];
+
+    debug!("JSON entry complete");
+
+    Ok(Entry::new(
+        author,
+        &objects,
+        target.as_ref(),
+        &alternate,
+        &application,
+        content.as_ref(),
+        &id,
+        published,
+        &timezone_offset,
+        &title,
+        updated,
+        &verbs,
+    )
+    .with_links(&read_json_links(activity))
+    .with_categories(&read_json_categories(activity))
+    .with_in_reply_to(read_json_reply_context(activity).as_ref())
+    .with_generator(generator.as_ref())
+    .with_icon(get_link(activity, "icon").as_ref()))
+}
+
+/// Reads `url` property, plain URL becomes alternate link.
+fn read_json_links(activity: &Map<String, Value>) -> Vec<Link> {
+    let urls = match activity.get("url") {
+        Some(Value::Array(urls)) => urls.iter().collect(),
+        Some(url) => vec![url],
+        None => Vec::with_capacity(0),
+    };
+
+    urls.into_iter()
+        .filter_map(|url| match url {
+            Value::String(href) => Some(Link::new("alternate", href, None, None)),
+            Value::Object(link) => {
+                let href = get_string(link, "href")?;
+                let rel = get_string(link, "rel").unwrap_or_else(|| "alternate".into());
+
+                Some(Link::new(
+                    &rel,
+                    &href,
+                    get_string(link, "mediaType").as_ref(),
+                    get_string(link, "name").as_ref(),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reads `tag` property, tag identifier or name becomes category term.
+fn read_json_categories(activity: &Map<String, Value>) -> Vec<Category> {
+    let tags = match activity.get("tag") {
+        Some(Value::Array(tags)) => tags.iter().collect(),
+        Some(tag) => vec![tag],
+        None => Vec::with_capacity(0),
+    };
+
+    tags.into_iter()
+        .filter_map(|tag| match tag {
+            Value::String(term) => Some(Category::new(term, None, None)),
+            Value::Object(tag) => {
+                let label = get_string(tag, "name");
+                let term = get_string(tag, "id").or_else(|| label.clone())?;
+
+                Some(Category::new(&term, None, label.as_ref()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn read_json_reply_context(activity: &Map<String, Value>) -> Option<ReplyContext> {
+    match activity.get("inReplyTo")? {
+        Value::String(reference) => Some(ReplyContext::new(reference, None, None)),
+        Value::Object(object) => {
+            let reference = get_string(object, "id")?;
+            let href = get_link(object, "url");
+
+            Some(ReplyContext::new(&reference, href.as_ref(), None))
+        }
+        _ => None,
+    }
+}
+
+fn read_json_object(value: &Value) -> ObjectResult<Object> {
+    debug!("parsing JSON object from {:?}", value);
+
+    let object = match value {
+        Value::Object(object) => object,
+        Value::String(link) => {
+            let mut properties = HashMap::with_capacity(1);
+            properties.insert("id".into(), link.clone());
+
+            return Ok(Object::unknown("Link", &properties));
+        }
+        _ => return Err(ObjectError::MissingObjectType),
+    };
+    let object_type = get_string(object, "type").ok_or(ObjectError::MissingObjectType)?;
+    let id = get_string(object, "id").ok_or_else(|| ObjectError::element_not_found("id"))?;
+    let title = get_string(object, "name").unwrap_or_else(|| id.clone());
+    let alternate = get_link(object, "url").unwrap_or_else(|| id.clone());
+
+    match object_type.as_str() {
+        "Person" | "Service" | "Application" => {
+            let username = get_string(object, "preferredUsername").unwrap_or_else(|| title.clone());
+            let photo = get_link(object, "icon");
+
+            Ok(Object::person(
+                &title,
+                None,
+                &alternate,
+                photo.as_deref(),
+                &username,
+            ))
+        }
+        "Note" => Ok(Object::comment(&id, &alternate)),
+        "Document" | "Image" | "Video" | "Audio" => Ok(Object::file(&id, &title, &alternate)),
+        "Page" => Ok(Object::page(&id, &title, &alternate)),
+        "Article" => Ok(Object::blog_post(&id, &title, &alternate)),
+        _ => {
+            warn!("Unknown JSON object type: {}", object_type);
+
+            let properties = object
+                .iter()
+                .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.into())))
+                .collect();
+
+            Ok(Object::unknown(&object_type, &properties))
+        }
+    }
+}
+
+/// Converts Activity Streams 2.0 activity type to Activity Streams 1.0 verb, so both formats
+/// can be matched with same activity rules.
+fn activity_verb(activity_type: &str) -> String {
+    let verb = match activity_type {
+        "Create" => "post",
+        "Add" => "add",
+        "Update" => "update",
+        "Delete" => "delete",
+        "Like" => "like",
+        "Follow" => "follow",
+        "Join" => "join",
+        other => return format!("{}{}", VERB_PREFIX, other.to_lowercase()),
+    };
+
+    format!("{}{}", VERB_PREFIX, verb)
+}
+
+fn read_timestamp(value: &str, element_name: &str) -> EntryResult<Timestamp> {
+    parse_timestamp(value).ok_or_else(|| EntryError::invalid_timestamp(element_name, value))
+}
+
+/// Returns time zone offset of given time in Atom feed format `+hhmm`.
+fn timezone_offset(time: &Timestamp) -> String {
+    time.format("%z").to_string()
+}
+
+fn get_string(object: &Map<String, Value>, name: &str) -> Option<String> {
+    object.get(name).and_then(|v| v.as_str()).map(|v| v.into())
+}
+
+/// Returns link target, link can be either string or `Link` object with `href` property.
+fn get_link(object: &Map<String, Value>, name: &str) -> Option<String> {
+    match object.get(name) {
+        Some(Value::String(href)) => Some(href.clone()),
+        Some(Value::Object(link)) => get_string(link, "href").or_else(|| get_string(link, "url")),
+        Some(Value::Array(links)) => links
+            .iter()
+            .filter_map(|v| v.as_str())
+            .next()
+            .map(|v| v.into()),
+        _ => None,
+    }
+}
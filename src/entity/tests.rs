@@ -0,0 +1,532 @@
+use chrono::DateTime;
+use serde_json;
+use serde_yaml;
+
+use super::*;
+
+const JIRA: &[u8] = include_bytes!("../../fixtures/jira.xml");
+const CONFLUENCE: &[u8] = include_bytes!("../../fixtures/confluence.xml");
+const BITBUCKET: &[u8] = include_bytes!("../../fixtures/bitbucket.xml");
+const FISHEYE: &[u8] = include_bytes!("../../fixtures/fisheye.xml");
+const EMPTY: &[u8] = include_bytes!("../../fixtures/empty.xml");
+const MISSING_ELEMENTS: &[u8] = include_bytes!("../../fixtures/missing_elements.xml");
+const INVALID_TIMESTAMP: &[u8] = include_bytes!("../../fixtures/invalid_timestamp.xml");
+const UNKNOWN_OBJECT: &[u8] = include_bytes!("../../fixtures/unknown_object.xml");
+const NESTED_ENTRY: &[u8] = include_bytes!("../../fixtures/nested_entry.xml");
+const MULTIPLE_LINKS: &[u8] = include_bytes!("../../fixtures/multiple_links.xml");
+const LEGACY_ENTRY: &str = include_str!("../../fixtures/legacy_entry.yaml");
+const MALFORMED: &[u8] = include_bytes!("../../fixtures/malformed.xml");
+const ACTIVITY_STREAMS2: &[u8] = include_bytes!("../../fixtures/activity_streams2.json");
+
+fn timestamp(value: &str) -> Timestamp {
+    DateTime::parse_from_rfc3339(value).unwrap()
+}
+
+fn read_results(input: &[u8]) -> Vec<EntryResult<Entry>> {
+    FeedReader::new(input).collect()
+}
+
+#[test]
+fn read_jira_feed() {
+    let feed = read(JIRA).unwrap();
+
+    assert_eq!(
+        feed.id(),
+        "https://jira.example.com/jira/plugins/servlet/streams"
+    );
+    assert_eq!(feed.title(), "Activity Streams");
+    assert_eq!(feed.timezone_offset(), "+0300");
+    assert_eq!(feed.updated(), timestamp("2018-03-14T09:15:42.123Z"));
+    assert_eq!(feed.entries().len(), 3);
+}
+
+#[test]
+fn read_jira_created_issue() {
+    let feed = read(JIRA).unwrap();
+    let entry = &feed.entries()[0];
+
+    assert_eq!(entry.id(), "urn:uuid:0f1d3c2a-6f0e-3d3c-9b43-1f6a8e0c2b11");
+    assert_eq!(entry.application(), "com.atlassian.jira");
+    assert_eq!(entry.timezone_offset(), "+0300");
+    assert_eq!(entry.published(), timestamp("2018-03-14T09:10:00Z"));
+    assert_eq!(entry.published().offset().local_minus_utc(), 3 * 60 * 60);
+    assert_eq!(entry.verbs(), ["http://activitystrea.ms/schema/1.0/post"]);
+    assert_eq!(
+        entry.alternate(),
+        "https://jira.example.com/jira/browse/PROJ-101"
+    );
+    assert!(entry.content().is_none());
+    assert!(!entry.is_degraded());
+
+    match entry.author() {
+        Object::Person {
+            ref name,
+            ref email,
+            ref photo,
+            ref username,
+            ..
+        } => {
+            assert_eq!(name, "John Doe");
+            assert_eq!(email.as_ref().unwrap(), "jdoe@example.com");
+            assert_eq!(
+                photo.as_ref().unwrap(),
+                "https://jira.example.com/jira/secure/useravatar?avatarId=10122"
+            );
+            assert_eq!(username, "jdoe");
+        }
+        author => panic!("Unexpected author {:?}", author),
+    }
+
+    assert_eq!(entry.objects().len(), 1);
+
+    let issue = &entry.objects()[0];
+    let key = issue.issue_key().unwrap();
+
+    assert_eq!(format!("{}", issue), "PROJ-101");
+    assert_eq!(issue.summary(), Some("Login fails with expired session"));
+    assert_eq!(key.key(), "PROJ-101");
+    assert_eq!(key.project(), "PROJ");
+    assert_eq!(key.number(), 101);
+    assert!(entry.mentions().is_empty());
+    assert_eq!(entry.projects(), ["PROJ"]);
+}
+
+#[test]
+fn read_jira_entry_links() {
+    let feed = read(JIRA).unwrap();
+    let entry = &feed.entries()[0];
+    let icon = entry
+        .link("http://streams.atlassian.com/syndication/icon")
+        .unwrap();
+
+    assert_eq!(entry.links().len(), 2);
+    assert_eq!(icon.title().unwrap(), "Bug");
+    assert_eq!(
+        entry.icon().unwrap(),
+        "https://jira.example.com/jira/images/icons/issuetypes/bug.png"
+    );
+    assert_eq!(entry.categories().len(), 1);
+    assert_eq!(entry.categories()[0].term(), "created");
+    assert_eq!(entry.generator().unwrap(), "https://jira.example.com/jira");
+    assert!(entry.in_reply_to().is_none());
+}
+
+#[test]
+fn read_multiple_alternate_links() {
+    let feed = read(MULTIPLE_LINKS).unwrap();
+    let entry = &feed.entries()[0];
+    let alternates: Vec<&str> = entry
+        .links()
+        .iter()
+        .filter(|link| link.rel() == "alternate")
+        .map(|link| link.href())
+        .collect();
+
+    assert_eq!(
+        entry.alternate(),
+        "https://jira.example.com/jira/browse/PROJ-1?focusedCommentId=10001"
+    );
+    assert_eq!(
+        alternates,
+        vec![
+            "https://jira.example.com/jira/browse/PROJ-1",
+            "https://jira.example.com/jira/browse/PROJ-1?focusedCommentId=10001",
+        ]
+    );
+    assert_eq!(entry.links().len(), 3);
+    assert_eq!(
+        entry.icon().unwrap(),
+        "https://jira.example.com/jira/images/icons/issuetypes/bug.png"
+    );
+}
+
+#[test]
+fn read_jira_comment() {
+    let feed = read(JIRA).unwrap();
+    let entry = &feed.entries()[1];
+    let content = entry.content_text().unwrap();
+
+    assert_eq!(entry.updated(), timestamp("2018-03-14T09:15:42.123Z"));
+    assert_eq!(content.text(), "Caused by CORE-7, fix is ready for review.");
+    assert_eq!(content.links().len(), 1);
+    assert_eq!(content.links()[0].text(), "CORE-7");
+    assert_eq!(
+        content.links()[0].href(),
+        "https://jira.example.com/jira/browse/CORE-7"
+    );
+
+    match entry.objects() {
+        [Object::Comment { .. }] => {}
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    let target = entry.target().unwrap();
+
+    assert_eq!(target.issue_key().unwrap().key(), "PROJ-101");
+    assert_eq!(entry.mentions().len(), 1);
+    assert_eq!(entry.mentions()[0].key(), "CORE-7");
+}
+
+#[test]
+fn read_entry_stored_without_issue_keys() {
+    let entry: Entry = serde_yaml::from_str(LEGACY_ENTRY).unwrap();
+    let keys: Vec<String> = entry
+        .issue_keys()
+        .iter()
+        .map(|key| key.key().into())
+        .collect();
+
+    assert_eq!(keys, vec!["PROJ-101".to_string()]);
+    assert_eq!(entry.target().unwrap().issue_key().unwrap().number(), 101);
+    assert_eq!(entry.mentions().len(), 1);
+    assert_eq!(entry.mentions()[0].key(), "CORE-7");
+    assert_eq!(entry.projects(), ["PROJ"]);
+}
+
+#[test]
+fn read_jira_transition() {
+    let feed = read(JIRA).unwrap();
+    let entry = &feed.entries()[2];
+
+    assert_eq!(entry.verbs().len(), 3);
+    assert_eq!(
+        entry.verbs()[2],
+        "http://streams.atlassian.com/syndication/verbs/jira/resolve"
+    );
+    assert!(entry.is_degraded());
+
+    match entry.author() {
+        Object::Person {
+            ref email,
+            ref photo,
+            ..
+        } => {
+            assert!(email.is_none());
+            assert!(photo.is_none());
+        }
+        author => panic!("Unexpected author {:?}", author),
+    }
+}
+
+#[test]
+fn read_confluence_feed() {
+    let feed = read(CONFLUENCE).unwrap();
+    let entries = feed.entries();
+
+    assert_eq!(entries.len(), 3);
+
+    match (&entries[0].objects()[0], entries[0].target()) {
+        (
+            &Object::Page { ref title, .. },
+            Some(&Object::Space {
+                title: ref space, ..
+            }),
+        ) => {
+            assert_eq!(title, "Release Checklist");
+            assert_eq!(space, "Team Space");
+        }
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    assert_eq!(
+        entries[0].content_text().unwrap().text(),
+        "Updated deployment steps\nRemoved obsolete & duplicated items"
+    );
+
+    match entries[1].objects() {
+        [Object::BlogPost { ref title, .. }] => assert_eq!(title, "Sprint 12 Retrospective"),
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    match (&entries[2].objects()[0], entries[2].target()) {
+        (&Object::Attachment { ref alternate, .. }, Some(&Object::Page { .. })) => assert_eq!(
+            alternate,
+            "https://wiki.example.com/download/attachments/10450/rollback-plan.pdf"
+        ),
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+}
+
+#[test]
+fn read_bitbucket_feed() {
+    let feed = read(BITBUCKET).unwrap();
+    let entries = feed.entries();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(feed.updated().offset().local_minus_utc(), 60 * 60);
+
+    match (&entries[0].objects()[0], entries[0].target()) {
+        (&Object::PullRequest { ref summary, .. }, Some(&Object::Repository { ref title, .. })) => {
+            assert_eq!(summary, "Session is refreshed before request is retried.");
+            assert_eq!(title, "portal");
+        }
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    assert_eq!(entries[0].mentions().len(), 1);
+    assert_eq!(entries[0].mentions()[0].key(), "PROJ-101");
+    assert_eq!(entries[0].projects(), ["PROJ"]);
+
+    let commits: Vec<_> = entries[1]
+        .objects()
+        .iter()
+        .map(|o| match o {
+            Object::Commit { ref title, .. } => title.as_str(),
+            object => panic!("Unexpected object {:?}", object),
+        })
+        .collect();
+
+    assert_eq!(commits, ["9f3c2e1", "4b7a0d8"]);
+}
+
+#[test]
+fn read_fisheye_feed() {
+    let feed = read(FISHEYE).unwrap();
+    let entries = feed.entries();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(feed.timezone_offset(), "-0500");
+
+    match entries[0].objects() {
+        [Object::Changeset { ref title, .. }] => assert_eq!(title, "18231"),
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    assert_eq!(entries[0].mentions()[0].key(), "CORE-7");
+
+    match entries[1].objects() {
+        [Object::Review { ref title, .. }] => assert_eq!(title, "CR-LEG-55"),
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    // Review keys contain several dashes and are not issue keys.
+    assert!(entries[1].mentions().is_empty());
+}
+
+#[test]
+fn read_empty_feed() {
+    let feed = read(EMPTY).unwrap();
+
+    assert!(feed.entries().is_empty());
+    assert_eq!(feed.updated(), timestamp("2018-03-14T09:15:42.123Z"));
+}
+
+#[test]
+fn read_missing_elements() {
+    let results = read_results(MISSING_ELEMENTS);
+
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0].as_ref().unwrap().id(), "urn:uuid:entry-1");
+
+    match results[1] {
+        Err(EntryError::ElementNotFound { ref element_name }) => {
+            assert_eq!(element_name, "published")
+        }
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    match results[2] {
+        Err(EntryError::ReadObjectError { ref message }) => {
+            assert_eq!(message, "Missing object type")
+        }
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    match results[3] {
+        Err(EntryError::ReadObjectError { ref message }) => {
+            assert_eq!(message, "Element `username` not found")
+        }
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    assert_eq!(results[4].as_ref().unwrap().id(), "urn:uuid:entry-5");
+
+    match read(MISSING_ELEMENTS) {
+        Err(FeedError::ReadEntryError { .. }) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn read_invalid_timestamp() {
+    let results = read_results(INVALID_TIMESTAMP);
+
+    assert_eq!(results.len(), 2);
+
+    match results[0] {
+        Err(EntryError::InvalidTimestamp {
+            ref element_name,
+            ref value,
+        }) => {
+            assert_eq!(element_name, "published");
+            assert_eq!(value, "14.03.2018 09:00");
+        }
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    let entry = results[1].as_ref().unwrap();
+
+    assert_eq!(entry.published(), timestamp("2018-03-14T03:30:00.123456Z"));
+    assert_eq!(entry.published().offset().local_minus_utc(), 330 * 60);
+}
+
+#[test]
+fn read_unknown_object() {
+    let feed = read(UNKNOWN_OBJECT).unwrap();
+    let entries = feed.entries();
+
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].is_degraded());
+    assert!(!entries[1].is_degraded());
+
+    let object = &entries[0].objects()[0];
+
+    assert_eq!(
+        object.object_type(),
+        "http://streams.atlassian.com/syndication/types/deployment"
+    );
+    assert_eq!(object.id(), Some("urn:uuid:deployment-17"));
+    assert_eq!(
+        object.alternate(),
+        Some("https://deploy.example.com/deployments/17")
+    );
+    assert_eq!(format!("{}", object), "Deployment 17");
+}
+
+#[test]
+fn read_nested_entry() {
+    let results = read_results(NESTED_ENTRY);
+
+    assert_eq!(results.len(), 2);
+
+    // Parent entry with nested entry is rejected, reading continues with following entry.
+    match results[0] {
+        Err(EntryError::NestedEntry { ref id }) => {
+            assert_eq!(id.as_ref().map(String::as_str), Some("urn:uuid:entry-1"))
+        }
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    assert_eq!(results[1].as_ref().unwrap().id(), "urn:uuid:entry-3");
+}
+
+#[test]
+fn read_malformed_feed() {
+    let results = read_results(MALFORMED);
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().id(), "urn:uuid:entry-1");
+
+    match results[1] {
+        Err(EntryError::XmlEventError { .. }) => {}
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    assert!(read(MALFORMED).is_err());
+}
+
+#[test]
+fn read_entries_detects_format() {
+    let atom: Vec<_> = read_entries(FeedFormat::Auto, JIRA).unwrap().collect();
+    let json: Vec<_> = read_entries(FeedFormat::Auto, ACTIVITY_STREAMS2)
+        .unwrap()
+        .collect();
+
+    assert_eq!(atom.len(), 3);
+    assert!(atom.iter().all(|e| e.is_ok()));
+    assert_eq!(json.len(), 3);
+    assert!(read_entries(FeedFormat::ActivityStreams2, JIRA).is_err());
+}
+
+#[test]
+fn read_entries_detects_format_after_whitespace_of_several_reads() {
+    // First read of chain returns only whitespace.
+    let json: Vec<_> = read_entries(FeedFormat::Auto, (&b" \n\n "[..]).chain(ACTIVITY_STREAMS2))
+        .unwrap()
+        .collect();
+    let atom: Vec<_> = read_entries(FeedFormat::Auto, (&b"\n"[..]).chain(JIRA))
+        .unwrap()
+        .collect();
+
+    assert_eq!(json.len(), 3);
+    assert_eq!(atom.len(), 3);
+    assert!(atom.iter().all(|e| e.is_ok()));
+}
+
+#[test]
+fn feed_format_from_media_type() {
+    assert_eq!(
+        FeedFormat::from_media_type("application/activity+json"),
+        Some(FeedFormat::ActivityStreams2)
+    );
+    assert_eq!(
+        FeedFormat::from_media_type("application/json; charset=UTF-8"),
+        Some(FeedFormat::ActivityStreams2)
+    );
+    assert_eq!(
+        FeedFormat::from_media_type("Application/Atom+XML;charset=UTF-8"),
+        Some(FeedFormat::Atom)
+    );
+    assert_eq!(FeedFormat::from_media_type("text/html"), None);
+}
+
+#[test]
+fn read_activity_streams2() {
+    let value: serde_json::Value = serde_json::from_slice(ACTIVITY_STREAMS2).unwrap();
+    let results = read_json_entries(&value).unwrap();
+
+    assert_eq!(results.len(), 3);
+
+    let entry = results[0].as_ref().unwrap();
+
+    assert_eq!(entry.verbs(), ["http://activitystrea.ms/schema/1.0/post"]);
+    assert_eq!(entry.application(), "mastodon");
+    assert_eq!(entry.timezone_offset(), "+0300");
+    assert_eq!(entry.updated(), entry.published());
+    assert_eq!(entry.generator().unwrap(), "https://social.example.com");
+    assert_eq!(entry.categories()[0].term(), "#release");
+    assert_eq!(
+        entry.in_reply_to().unwrap().reference(),
+        "https://social.example.com/notes/76"
+    );
+    assert_eq!(entry.mentions()[0].key(), "PROJ-101");
+
+    let entry = results[1].as_ref().unwrap();
+
+    assert_eq!(entry.verbs(), ["http://activitystrea.ms/schema/1.0/like"]);
+    assert_eq!(entry.updated(), timestamp("2018-03-13T18:05:00Z"));
+
+    match entry.objects() {
+        [Object::BlogPost { ref title, .. }] => assert_eq!(title, "Release notes"),
+        objects => panic!("Unexpected objects {:?}", objects),
+    }
+
+    match results[2] {
+        Err(EntryError::InvalidTimestamp { ref value, .. }) => assert_eq!(value, "yesterday"),
+        ref result => panic!("Unexpected result {:?}", result),
+    }
+
+    assert!(read_json_feed(&value).is_err());
+}
+
+#[test]
+fn write_and_read_feed() {
+    let feed = read(JIRA).unwrap();
+    let mut output = Vec::new();
+
+    write_feed(&feed, &[("self", feed.id())], &mut output).unwrap();
+
+    let written = read(&output[..]).unwrap();
+
+    assert_eq!(written.entries().len(), feed.entries().len());
+
+    for (written, entry) in written.entries().iter().zip(feed.entries()) {
+        assert_eq!(written.id(), entry.id());
+        assert_eq!(written.published(), entry.published());
+        assert_eq!(written.verbs(), entry.verbs());
+        assert_eq!(written.links(), entry.links());
+        assert_eq!(written.categories(), entry.categories());
+        assert_eq!(written.mentions(), entry.mentions());
+        assert_eq!(written.content(), entry.content());
+    }
+}
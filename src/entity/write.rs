@@ -0,0 +1,273 @@
+use std::io::Write;
+use xml::writer::EventWriter;
+use xml::writer::XmlEvent;
+use xml::EmitterConfig;
+
+use super::Entry;
+use super::Feed;
+use super::Link;
+use super::Object;
+use super::WriteError;
+use super::WriteResult;
+
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+const ACTIVITY_NAMESPACE: &str = "http://activitystrea.ms/spec/1.0/";
+const ATLASSIAN_NAMESPACE: &str = "http://streams.atlassian.com/syndication/general/1.0";
+const USERNAME_NAMESPACE: &str = "http://streams.atlassian.com/syndication/username/1.0";
+const THREADING_NAMESPACE: &str = "http://purl.org/syndication/thread/1.0";
+
+/// Writes feed as Atom document with activity streams extension. Written feed can be read back
+/// with `read`. Every link contains relation and target.
+pub fn write_feed<W>(feed: &Feed, links: &[(&str, &str)], write: W) -> WriteResult<()>
+where
+    W: Write,
+{
+    let mut writer = EmitterConfig::new()
+        .perform_indent(true)
+        .create_writer(write);
+
+    start_element(
+        &mut writer,
+        XmlEvent::start_element("feed")
+            .default_ns(ATOM_NAMESPACE)
+            .ns("activity", ACTIVITY_NAMESPACE)
+            .ns("atlassian", ATLASSIAN_NAMESPACE)
+            .ns("usr", USERNAME_NAMESPACE)
+            .ns("thr", THREADING_NAMESPACE),
+    )?;
+    write_text(&mut writer, "id", feed.id())?;
+    write_text(&mut writer, "title", feed.title())?;
+    write_text(
+        &mut writer,
+        "atlassian:timezone-offset",
+        feed.timezone_offset(),
+    )?;
+    write_text(&mut writer, "updated", &feed.updated().to_rfc3339())?;
+
+    for &(rel, href) in links {
+        write_link(&mut writer, rel, href)?;
+    }
+
+    for entry in feed.entries() {
+        write_entry(&mut writer, entry)?;
+    }
+
+    end_element(&mut writer)
+}
+
+fn write_entry<W>(writer: &mut EventWriter<W>, entry: &Entry) -> WriteResult<()>
+where
+    W: Write,
+{
+    start_element(writer, XmlEvent::start_element("entry"))?;
+    write_text(writer, "id", entry.id())?;
+    write_text(writer, "title", entry.title())?;
+
+    if let Some(content) = entry.content() {
+        start_element(
+            writer,
+            XmlEvent::start_element("content").attr("type", "html"),
+        )?;
+        write_characters(writer, content)?;
+        end_element(writer)?;
+    }
+
+    write_object(writer, "author", entry.author())?;
+    write_text(writer, "published", &entry.published().to_rfc3339())?;
+    write_text(writer, "updated", &entry.updated().to_rfc3339())?;
+
+    if entry.links().is_empty() {
+        write_link(writer, "alternate", entry.alternate())?;
+    }
+
+    for link in entry.links() {
+        write_entry_link(writer, link)?;
+    }
+
+    for category in entry.categories() {
+        let mut element = XmlEvent::start_element("category").attr("term", category.term());
+
+        if let Some(scheme) = category.scheme() {
+            element = element.attr("scheme", scheme);
+        }
+
+        if let Some(label) = category.label() {
+            element = element.attr("label", label);
+        }
+
+        start_element(writer, element)?;
+        end_element(writer)?;
+    }
+
+    if let Some(in_reply_to) = entry.in_reply_to() {
+        let mut element =
+            XmlEvent::start_element("thr:in-reply-to").attr("ref", in_reply_to.reference());
+
+        if let Some(href) = in_reply_to.href() {
+            element = element.attr("href", href);
+        }
+
+        if let Some(media_type) = in_reply_to.media_type() {
+            element = element.attr("type", media_type);
+        }
+
+        start_element(writer, element)?;
+        end_element(writer)?;
+    }
+
+    if let Some(generator) = entry.generator() {
+        write_text(writer, "generator", generator)?;
+    }
+
+    if let Some(icon) = entry.icon() {
+        write_text(writer, "icon", icon)?;
+    }
+
+    for verb in entry.verbs() {
+        write_text(writer, "activity:verb", verb)?;
+    }
+
+    for object in entry.objects() {
+        write_object(writer, "activity:object", object)?;
+    }
+
+    if let Some(target) = entry.target() {
+        write_object(writer, "activity:target", target)?;
+    }
+
+    write_text(writer, "atlassian:application", entry.application())?;
+    write_text(writer, "atlassian:timezone-offset", entry.timezone_offset())?;
+
+    end_element(writer)
+}
+
+fn write_object<W>(
+    writer: &mut EventWriter<W>,
+    element_name: &str,
+    object: &Object,
+) -> WriteResult<()>
+where
+    W: Write,
+{
+    start_element(writer, XmlEvent::start_element(element_name))?;
+
+    match object {
+        Object::Person {
+            ref name,
+            ref email,
+            ref uri,
+            ref photo,
+            ref username,
+        } => {
+            write_text(writer, "name", name)?;
+
+            if let Some(email) = email {
+                write_text(writer, "email", email)?;
+            }
+
+            write_text(writer, "uri", uri)?;
+
+            if let Some(photo) = photo {
+                write_link(writer, "photo", photo)?;
+            }
+
+            write_text(writer, "usr:username", username)?;
+        }
+        Object::Unknown { ref properties, .. } => {
+            for (name, value) in properties {
+                match name.as_str() {
+                    "object-type" => {}
+                    "alternate" => write_link(writer, name, value)?,
+                    _ => write_text(writer, name, value)?,
+                }
+            }
+        }
+        _ => {
+            if let Some(id) = object.id() {
+                write_text(writer, "id", id)?;
+            }
+
+            write_text(writer, "title", &format!("{}", object))?;
+
+            if let Some(summary) = object.summary() {
+                write_text(writer, "summary", summary)?;
+            }
+
+            if let Some(alternate) = object.alternate() {
+                write_link(writer, "alternate", alternate)?;
+            }
+        }
+    }
+
+    write_text(writer, "activity:object-type", object.object_type())?;
+
+    end_element(writer)
+}
+
+fn write_link<W>(writer: &mut EventWriter<W>, rel: &str, href: &str) -> WriteResult<()>
+where
+    W: Write,
+{
+    start_element(
+        writer,
+        XmlEvent::start_element("link")
+            .attr("rel", rel)
+            .attr("href", href),
+    )?;
+    end_element(writer)
+}
+
+fn write_entry_link<W>(writer: &mut EventWriter<W>, link: &Link) -> WriteResult<()>
+where
+    W: Write,
+{
+    let mut element = XmlEvent::start_element("link")
+        .attr("rel", link.rel())
+        .attr("href", link.href());
+
+    if let Some(media_type) = link.media_type() {
+        element = element.attr("type", media_type);
+    }
+
+    if let Some(title) = link.title() {
+        element = element.attr("title", title);
+    }
+
+    start_element(writer, element)?;
+    end_element(writer)
+}
+
+fn write_text<W>(writer: &mut EventWriter<W>, element_name: &str, text: &str) -> WriteResult<()>
+where
+    W: Write,
+{
+    start_element(writer, XmlEvent::start_element(element_name))?;
+    write_characters(writer, text)?;
+    end_element(writer)
+}
+
+fn start_element<'a, W, E>(writer: &mut EventWriter<W>, event: E) -> WriteResult<()>
+where
+    W: Write,
+    E: Into<XmlEvent<'a>>,
+{
+    writer.write(event).map_err(WriteError::xml_write_error)
+}
+
+fn write_characters<W>(writer: &mut EventWriter<W>, text: &str) -> WriteResult<()>
+where
+    W: Write,
+{
+    writer
+        .write(XmlEvent::characters(text))
+        .map_err(WriteError::xml_write_error)
+}
+
+fn end_element<W>(writer: &mut EventWriter<W>) -> WriteResult<()>
+where
+    W: Write,
+{
+    writer
+        .write(XmlEvent::end_element())
+        .map_err(WriteError::xml_write_error)
+}
@@ -0,0 +1,205 @@
+use serde_json::Map;
+use serde_json::Value;
+
+use super::Entry;
+use super::Feed;
+use super::Link;
+use super::Object;
+
+const VERB_PREFIX: &str = "http://activitystrea.ms/schema/1.0/";
+const CONTEXT: &str = "https://www.w3.org/ns/activitystreams";
+
+/// Converts feed to Activity Streams 2.0 ordered collection page. Links with relations `next`,
+/// `prev` and `self` become `next`, `prev` and `id` properties of collection.
+pub fn write_json_feed(feed: &Feed, links: &[(&str, &str)]) -> Value {
+    let mut collection = Map::new();
+    collection.insert("@context".into(), CONTEXT.into());
+    collection.insert("type".into(), "OrderedCollectionPage".into());
+    collection.insert("id".into(), feed.id().into());
+    collection.insert("name".into(), feed.title().into());
+    collection.insert("updated".into(), feed.updated().to_rfc3339().into());
+    collection.insert("totalItems".into(), feed.entries().len().into());
+
+    for &(rel, href) in links {
+        match rel {
+            "self" => collection.insert("id".into(), href.into()),
+            "next" | "prev" | "first" | "last" => collection.insert(rel.into(), href.into()),
+            _ => None,
+        };
+    }
+
+    let items = feed.entries().iter().map(write_json_entry).collect();
+    collection.insert("orderedItems".into(), Value::Array(items));
+
+    Value::Object(collection)
+}
+
+fn write_json_entry(entry: &Entry) -> Value {
+    let mut activity = Map::new();
+    activity.insert("id".into(), entry.id().into());
+    activity.insert("type".into(), activity_type(entry.verbs()).into());
+    activity.insert("name".into(), entry.title().into());
+    activity.insert("actor".into(), write_json_object(entry.author()));
+    activity.insert("url".into(), entry.alternate().into());
+    activity.insert("published".into(), entry.published().to_rfc3339().into());
+    activity.insert("updated".into(), entry.updated().to_rfc3339().into());
+
+    let mut generator = Map::new();
+    generator.insert("type".into(), "Application".into());
+    generator.insert("name".into(), entry.application().into());
+
+    if let Some(id) = entry.generator() {
+        generator.insert("id".into(), id.as_str().into());
+    }
+
+    activity.insert("generator".into(), Value::Object(generator));
+
+    if let Some(content) = entry.content() {
+        activity.insert("content".into(), content.as_str().into());
+    }
+
+    if !entry.links().is_empty() {
+        let links = entry.links().iter().map(write_json_link).collect();
+
+        activity.insert("url".into(), Value::Array(links));
+    }
+
+    if !entry.categories().is_empty() {
+        let tags = entry
+            .categories()
+            .iter()
+            .map(|category| {
+                let mut tag = Map::new();
+                tag.insert("type".into(), "Object".into());
+                tag.insert("id".into(), category.term().into());
+
+                if let Some(label) = category.label() {
+                    tag.insert("name".into(), label.as_str().into());
+                }
+
+                Value::Object(tag)
+            })
+            .collect();
+
+        activity.insert("tag".into(), Value::Array(tags));
+    }
+
+    if let Some(in_reply_to) = entry.in_reply_to() {
+        activity.insert("inReplyTo".into(), in_reply_to.reference().into());
+    }
+
+    if let Some(icon) = entry.icon() {
+        activity.insert("icon".into(), icon.as_str().into());
+    }
+
+    match entry.objects() {
+        [] => {}
+        [object] => {
+            activity.insert("object".into(), write_json_object(object));
+        }
+        objects => {
+            let objects = objects.iter().map(write_json_object).collect();
+
+            activity.insert("object".into(), Value::Array(objects));
+        }
+    }
+
+    if let Some(target) = entry.target() {
+        activity.insert("target".into(), write_json_object(target));
+    }
+
+    Value::Object(activity)
+}
+
+fn write_json_link(link: &Link) -> Value {
+    let mut result = Map::new();
+    result.insert("type".into(), "Link".into());
+    result.insert("rel".into(), link.rel().into());
+    result.insert("href".into(), link.href().into());
+
+    if let Some(media_type) = link.media_type() {
+        result.insert("mediaType".into(), media_type.as_str().into());
+    }
+
+    if let Some(title) = link.title() {
+        result.insert("name".into(), title.as_str().into());
+    }
+
+    Value::Object(result)
+}
+
+fn write_json_object(object: &Object) -> Value {
+    let mut result = Map::new();
+
+    if let Object::Person {
+        ref name,
+        ref uri,
+        ref photo,
+        ref username,
+        ..
+    } = object
+    {
+        result.insert("type".into(), "Person".into());
+        result.insert("id".into(), uri.as_str().into());
+        result.insert("name".into(), name.as_str().into());
+        result.insert("preferredUsername".into(), username.as_str().into());
+
+        if let Some(photo) = photo {
+            result.insert("icon".into(), photo.as_str().into());
+        }
+
+        return Value::Object(result);
+    }
+
+    let object_type = match object {
+        Object::Comment { .. } => "Note",
+        Object::File { .. } | Object::Attachment { .. } => "Document",
+        Object::Page { .. } => "Page",
+        Object::BlogPost { .. } => "Article",
+        _ => object.object_type(),
+    };
+
+    result.insert("type".into(), object_type.into());
+    result.insert("name".into(), format!("{}", object).into());
+
+    if let Some(id) = object.id() {
+        result.insert("id".into(), id.into());
+    }
+
+    if let Some(summary) = object.summary() {
+        result.insert("summary".into(), summary.into());
+    }
+
+    if let Some(alternate) = object.alternate() {
+        result.insert("url".into(), alternate.into());
+    }
+
+    Value::Object(result)
+}
+
+/// Converts last activity verb to Activity Streams 2.0 activity type.
+fn activity_type(verbs: &[String]) -> String {
+    let verb = verbs
+        .last()
+        .map(|verb| verb.trim_start_matches(VERB_PREFIX))
+        .unwrap_or("update");
+
+    match verb {
+        "post" => "Create".into(),
+        "add" => "Add".into(),
+        "update" => "Update".into(),
+        "delete" => "Delete".into(),
+        "like" => "Like".into(),
+        "follow" => "Follow".into(),
+        "join" => "Join".into(),
+        _ if verb.starts_with("http") => "Update".into(),
+        other => {
+            let mut chars = other.chars();
+
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => "Update".into(),
+            }
+        }
+    }
+}
@@ -4,19 +4,28 @@ extern crate log;
 #[macro_use]
 extern crate serde_derive;
 
+extern crate chrono;
+extern crate ctrlc;
 extern crate env_logger;
+extern crate flate2;
 extern crate iron;
 extern crate mount;
+extern crate native_tls;
+extern crate postgres;
+extern crate postgres_native_tls;
 extern crate rand;
 extern crate reqwest;
 extern crate router;
 extern crate serde_json;
 extern crate serde_yaml;
 extern crate sqlite;
+extern crate sqlite3_sys;
 extern crate staticfile;
 extern crate time;
 extern crate xml;
 
+mod backup;
+mod command;
 mod config;
 mod database;
 mod entity;
@@ -24,9 +33,20 @@ mod server;
 mod stream;
 mod worker;
 
+#[cfg(test)]
+mod tests;
+
+use backup::BackupWorker;
+use command::Command;
 use config::Config;
+use config::DatabaseBackend;
 use database::Database;
+use database::SearchQuery;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::Duration;
 use worker::Worker;
+use worker::WorkerState;
 
 fn main() {
     env_logger::init();
@@ -36,21 +56,151 @@ fn main() {
         Err(err) => panic!("Failed to create configuration: {}", err),
     };
 
+    let command = match Command::from_args() {
+        Ok(command) => command,
+        Err(err) => panic!("Invalid command: {}", err),
+    };
+
     let database = match Database::new(&config) {
         Ok(database) => database,
         Err(err) => panic!("Failed to initialize database: {}", err),
     };
 
+    match command {
+        Command::Serve => serve(&config, database),
+        Command::Import { ref paths } => import(&config, &database, paths),
+        Command::Export { ref path } => export(&database, path),
+        Command::Restore { ref path } => restore(&database, path),
+        Command::Search { ref query } => search(&database, query),
+    }
+}
+
+fn serve(config: &Config, database: Database) {
+    let worker_state = WorkerState::new(config.members());
     let join_worker = if config.start_worker() {
         let database = database.clone();
-        let worker = Worker::new(&config, database);
+        let worker = match Worker::new(config, database, worker_state.clone()) {
+            Ok(worker) => worker,
+            Err(err) => panic!("Failed to create worker: {}", err),
+        };
 
         Some(worker.start())
     } else {
         None
     };
+    let backup = match config.backup() {
+        Some(backup) if config.database().backend() != DatabaseBackend::Sqlite => {
+            warn!("Snapshots are supported only by SQLite database backend");
+
+            Some(
+                BackupWorker::new(backup, database.clone())
+                    .with_snapshots(false)
+                    .start(),
+            )
+        }
+        Some(backup) => Some(BackupWorker::new(backup, database.clone()).start()),
+        None => None,
+    };
+
+    let (shutdown_sender, shutdown_receiver) = mpsc::channel();
+
+    if let Err(err) = ctrlc::set_handler(move || {
+        let _ = shutdown_sender.send(());
+    }) {
+        panic!("Failed to set signal handler: {}", err);
+    }
+
+    let timeout = Duration::from_secs(config.shutdown_timeout());
+
+    if let Some(server) = server::start(config, database, worker_state.clone()) {
+        let _ = shutdown_receiver.recv();
+
+        println!("Shutting down...");
+
+        if !server.stop(timeout) {
+            warn!("Requests did not finish in {} seconds", timeout.as_secs());
+        }
+    }
+
+    worker_state.stop();
+
+    if let Some(join_worker) = join_worker {
+        if !join_timeout(join_worker, timeout) {
+            warn!("Worker did not stop in {} seconds", timeout.as_secs());
+        }
+    }
+
+    if let Some(backup) = backup {
+        if !join_timeout(backup.stop(), timeout) {
+            warn!("Backup did not stop in {} seconds", timeout.as_secs());
+        }
+    }
+}
+
+fn import(config: &Config, database: &Database, paths: &[String]) {
+    let stats = match command::import(config, database, paths) {
+        Ok(stats) => stats,
+        Err(err) => panic!("Failed to import: {}", err),
+    };
+
+    println!(
+        "Imported {} entries from {} files: {} new, {} updated, {} of other authors skipped",
+        stats.entries(),
+        stats.files(),
+        stats.saved().inserted(),
+        stats.saved().updated(),
+        stats.skipped()
+    );
+
+    if stats.failed_files() > 0 {
+        println!("Failed to read {} files", stats.failed_files());
+    }
+}
+
+fn export(database: &Database, path: &str) {
+    match command::export(database, path) {
+        Ok(stats) => println!("Exported {} to {}", stats, path),
+        Err(err) => panic!("Failed to export: {}", err),
+    }
+}
+
+fn restore(database: &Database, path: &str) {
+    match command::restore(database, path) {
+        Ok(stats) => println!("Restored {} from {}", stats, path),
+        Err(err) => panic!("Failed to restore: {}", err),
+    }
+}
+
+fn search(database: &Database, query: &SearchQuery) {
+    let matches = match command::search(database, query) {
+        Ok(matches) => matches,
+        Err(err) => panic!("Failed to search: {}", err),
+    };
+
+    for found in &matches {
+        let entry = found.entry();
+
+        println!(
+            "{} {} {}\n    {}\n    {}",
+            entry.published().format("%Y-%m-%d %H:%M"),
+            found.author(),
+            entry.title_text(),
+            found.snippet(),
+            entry.alternate()
+        );
+    }
+
+    println!("Found {} entries", matches.len());
+}
+
+/// Waits for thread to finish. Returns `false` if thread still running after timeout.
+fn join_timeout(handle: JoinHandle<()>, timeout: Duration) -> bool {
+    let (sender, receiver) = mpsc::channel();
 
-    server::start(&config, database);
+    std::thread::spawn(move || {
+        let _ = handle.join();
+        let _ = sender.send(());
+    });
 
-    drop(join_worker);
+    receiver.recv_timeout(timeout).is_ok()
 }
@@ -54,32 +54,6 @@ struct HandlerError {
     message: String,
 }
 
-macro_rules! try_msg {
-    ($ex:expr, $callback:expr) => {
-        match $ex {
-            Ok(value) => value,
-            Err(err) => {
-                warn!("{}", err);
-
-                let result = try_err!($callback(err));
-
-                return result;
-            }
-        }
-    };
-}
-
-macro_rules! try_err {
-    ($ex:expr) => {{
-        let response = match serde_json::to_string(&$ex) {
-            Ok(body) => Response::with((status::Ok, body)),
-            Err(_) => Response::with(status::InternalServerError),
-        };
-
-        Ok(response)
-    }};
-}
-
 impl ActivityHandler {
     pub fn new(config: &Config, database: Database) -> ActivityHandler {
         ActivityHandler {
@@ -0,0 +1,198 @@
+use chrono::Utc;
+use iron::middleware::Handler;
+use iron::mime::Mime;
+use iron::status;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use mount::OriginalUrl;
+use serde_json;
+use serde_yaml;
+use time;
+
+use config::Config;
+use database::Database;
+use database::FeedQuery;
+use entity;
+use entity::Entry;
+use entity::Feed;
+
+const PAGE_SIZE: usize = 50;
+const DEFAULT_PERIOD: i64 = 7 * 24 * 60 * 60;
+const FEED_TITLE: &str = "Team activity";
+const UTC_OFFSET: &str = "+0000";
+
+#[derive(Debug, Clone, Copy)]
+enum FeedFormat {
+    Atom,
+    Json,
+}
+
+/// Publishes stored activity of all members as Atom (`/atom`) or Activity Streams 2.0 (`/json`)
+/// feed. Feed can be filtered with query parameters `member` (can be repeated), `from` and `to`
+/// (UNIX time), `application` and paged with `page`. There are no named teams, feed of team is
+/// requested by repeating `member` for every team member, e.g. `?member=jdoe&member=asmith`.
+#[derive(Debug)]
+pub struct FeedHandler {
+    members: Vec<String>,
+    database: Database,
+}
+
+impl FeedHandler {
+    pub fn new(config: &Config, database: Database) -> FeedHandler {
+        FeedHandler {
+            members: config.members().into(),
+            database,
+        }
+    }
+
+    /// Returns requested page number and query of its entries with one extra entry, which tells
+    /// whether next page exists.
+    fn parse_query(&self, req: &Request) -> Option<(usize, FeedQuery)> {
+        let url = req.url.as_ref();
+        let mut members = Vec::new();
+        let mut start_date = None;
+        let mut end_date = None;
+        let mut application = None;
+        let mut page: usize = 0;
+
+        for (name, value) in url.query_pairs() {
+            match name.as_ref() {
+                "member" => members.push(value.into_owned()),
+                "from" => start_date = Some(value.parse().ok()?),
+                "to" => end_date = Some(value.parse().ok()?),
+                "application" => application = Some(value.into_owned()),
+                "page" => page = value.parse().ok()?,
+                _ => {}
+            }
+        }
+
+        if members.is_empty() {
+            members = self.members.clone();
+        } else {
+            members.retain(|member| self.members.contains(member));
+        }
+
+        let end_date = end_date.unwrap_or_else(|| time::get_time().sec);
+        let start_date = match start_date {
+            Some(start_date) => start_date,
+            None => end_date.checked_sub(DEFAULT_PERIOD)?,
+        };
+        let offset = page.checked_mul(PAGE_SIZE)?;
+        let query = FeedQuery::new(start_date, end_date, offset, PAGE_SIZE + 1)
+            .with_members(&members)
+            .with_application(application.as_ref());
+
+        Some((page, query))
+    }
+
+    /// Returns rows of entries matching query, members unknown to configuration have no entries.
+    fn rows(&self, query: &FeedQuery) -> Option<Vec<(String, String)>> {
+        if query.members().is_empty() {
+            return Some(Vec::with_capacity(0));
+        }
+
+        self.database.feed_entries(query).ok()
+    }
+}
+
+/// Deserializes stored entry, entries which cannot be read are skipped.
+fn read_entry(data: &str) -> Option<Entry> {
+    match serde_yaml::from_str(data) {
+        Ok(entry) => Some(entry),
+        Err(err) => {
+            warn!("Failed to deserialize entry: {}", err);
+
+            None
+        }
+    }
+}
+
+impl Handler for FeedHandler {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        let format = match req.url.path().first() {
+            Some(&"atom") => FeedFormat::Atom,
+            Some(&"json") => FeedFormat::Json,
+            _ => return Ok(Response::with(status::NotFound)),
+        };
+        let (page, query) = match self.parse_query(req) {
+            Some(query) => query,
+            None => return Ok(Response::with(status::BadRequest)),
+        };
+        let rows = match self.rows(&query) {
+            Some(rows) => rows,
+            None => return Ok(Response::with(status::InternalServerError)),
+        };
+        let page_entries: Vec<Entry> = rows
+            .iter()
+            .take(PAGE_SIZE)
+            .filter_map(|(_, data)| read_entry(data))
+            .collect();
+        let self_link = page_link(req, page);
+        let mut links = vec![("self", self_link.clone())];
+
+        if rows.len() > PAGE_SIZE {
+            links.push(("next", page_link(req, page + 1)));
+        }
+
+        if page > 0 {
+            links.push(("prev", page_link(req, page - 1)));
+        }
+
+        let links: Vec<_> = links
+            .iter()
+            .map(|&(rel, ref href)| (rel, href.as_str()))
+            .collect();
+        let updated = page_entries
+            .iter()
+            .map(|e| e.updated())
+            .max()
+            .unwrap_or_else(|| Utc::now().into());
+        let feed = Feed::new(&self_link, FEED_TITLE, UTC_OFFSET, updated, &page_entries);
+
+        match format {
+            FeedFormat::Atom => {
+                let mut body = Vec::new();
+
+                if entity::write_feed(&feed, &links, &mut body).is_err() {
+                    return Ok(Response::with(status::InternalServerError));
+                }
+
+                let mime: Mime = "application/atom+xml".parse().unwrap();
+
+                Ok(Response::with((status::Ok, mime, body)))
+            }
+            FeedFormat::Json => {
+                let value = entity::write_json_feed(&feed, &links);
+                let body = match serde_json::to_string(&value) {
+                    Ok(body) => body,
+                    Err(_) => return Ok(Response::with(status::InternalServerError)),
+                };
+                let mime: Mime = "application/activity+json".parse().unwrap();
+
+                Ok(Response::with((status::Ok, mime, body)))
+            }
+        }
+    }
+}
+
+/// Returns absolute URL of given feed page, all other query parameters will be preserved.
+fn page_link(req: &Request, page: usize) -> String {
+    let url = match req.extensions.get::<OriginalUrl>() {
+        Some(url) => url.clone(),
+        None => req.url.clone(),
+    };
+    let mut url = url.as_ref().clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| name != "page")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("page", &format!("{}", page));
+
+    url.into_string()
+}
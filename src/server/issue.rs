@@ -0,0 +1,119 @@
+use iron::middleware::Handler;
+use iron::status;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use serde_json;
+use serde_yaml;
+
+use config::Config;
+use database::Database;
+use entity::Entry;
+use entity::IssueKey;
+use entity::Timestamp;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueResponse {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issue_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entries: Option<Vec<TimelineEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+/// Entry referring to issue.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    member: String,
+    id: String,
+    published: Timestamp,
+    title: String,
+    link: String,
+    application: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+impl IssueResponse {
+    fn with_entries(issue_key: &IssueKey, entries: Vec<TimelineEntry>) -> IssueResponse {
+        IssueResponse {
+            success: true,
+            issue_key: Some(issue_key.key().into()),
+            entries: Some(entries),
+            message: None,
+        }
+    }
+
+    fn with_error(message: &str) -> IssueResponse {
+        IssueResponse {
+            success: false,
+            issue_key: None,
+            entries: None,
+            message: Some(message.into()),
+        }
+    }
+}
+
+impl TimelineEntry {
+    fn new(author: &str, data: &str) -> Option<TimelineEntry> {
+        let entry: Entry = match serde_yaml::from_str(data) {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("Failed to deserialize entry: {}", err);
+
+                return None;
+            }
+        };
+
+        Some(TimelineEntry {
+            member: author.into(),
+            id: entry.id().into(),
+            published: entry.published(),
+            title: entry.title_text(),
+            link: entry.alternate().into(),
+            application: entry.application().into(),
+            content: entry.content_text().map(|content| content.text().into()),
+        })
+    }
+}
+
+/// Returns all stored entries referring to issue given in path, e.g. `/PROJ-101`, as object or
+/// target. Entries of all members are ordered by publication time.
+#[derive(Debug)]
+pub struct IssueHandler {
+    database: Database,
+}
+
+impl IssueHandler {
+    pub fn new(_config: &Config, database: Database) -> IssueHandler {
+        IssueHandler { database }
+    }
+
+    fn parse_key(req: &Request) -> Result<IssueKey, &'static str> {
+        match req.url.path().as_slice() {
+            [""] => Err("Missing issue key"),
+            [key] => IssueKey::parse(key).ok_or("Invalid issue key"),
+            _ => Err("Missing issue key"),
+        }
+    }
+}
+
+impl Handler for IssueHandler {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        let issue_key = try_msg!(IssueHandler::parse_key(req), IssueResponse::with_error);
+        let entries = match self.database.issue_entries(issue_key.key()) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(Response::with(status::InternalServerError)),
+        };
+
+        try_err!(IssueResponse::with_entries(
+            &issue_key,
+            entries
+                .iter()
+                .filter_map(|(author, data)| TimelineEntry::new(author, data))
+                .collect()
+        ))
+    }
+}
@@ -1,28 +1,112 @@
+macro_rules! try_msg {
+    ($ex:expr, $callback:expr) => {
+        match $ex {
+            Ok(value) => value,
+            Err(err) => {
+                warn!("{}", err);
+
+                let result = try_err!($callback(err));
+
+                return result;
+            }
+        }
+    };
+}
+
+macro_rules! try_err {
+    ($ex:expr) => {{
+        let response = match serde_json::to_string(&$ex) {
+            Ok(body) => Response::with((status::Ok, body)),
+            Err(_) => Response::with(status::InternalServerError),
+        };
+
+        Ok(response)
+    }};
+}
+
 mod activity;
+mod feed;
+mod issue;
+mod search;
+mod shutdown;
+mod worker;
 
 use self::activity::ActivityHandler;
+use self::feed::FeedHandler;
+use self::issue::IssueHandler;
+use self::search::SearchHandler;
+use self::shutdown::ShutdownGuard;
+use self::worker::WorkerHandler;
 
+use iron::Chain;
 use iron::Iron;
+use iron::Listening;
 use mount::Mount;
 use staticfile::Static;
+use std::net::SocketAddr;
+use std::time::Duration;
 
+use worker::WorkerState;
 use Config;
 use Database;
 
-pub fn start(config: &Config, database: Database) -> () {
+/// Running HTTP server.
+#[derive(Debug)]
+pub struct Server {
+    listening: Listening,
+    guard: ShutdownGuard,
+}
+
+impl Server {
+    /// Returns address server is listening on, useful when server started on port `0`.
+    pub fn address(&self) -> SocketAddr {
+        self.listening.socket
+    }
+
+    /// Rejects new requests with `503 Service Unavailable` and waits up to given time for
+    /// requests being handled. Returns `false` if some requests did not finish in time. Listening
+    /// socket stays open until process exits, since hyper 0.10 cannot stop its accept loop.
+    pub fn stop(mut self, timeout: Duration) -> bool {
+        let finished = self.guard.stop(timeout);
+
+        // Only detaches accept loop, otherwise dropping `Listening` would wait for it forever.
+        if let Err(err) = self.listening.close() {
+            warn!("Failed to close HTTP server: {}", err);
+        }
+
+        finished
+    }
+}
+
+pub fn start(config: &Config, database: Database, worker_state: WorkerState) -> Option<Server> {
     let mut mount = Mount::new();
-    mount.mount("/api/v1/activity", ActivityHandler::new(config, database));
+    mount.mount(
+        "/api/v1/activity",
+        ActivityHandler::new(config, database.clone()),
+    );
+    mount.mount("/api/v1/feed", FeedHandler::new(config, database.clone()));
+    mount.mount("/api/v1/issue", IssueHandler::new(config, database.clone()));
+    mount.mount("/api/v1/search", SearchHandler::new(config, database));
+    mount.mount("/api/v1/worker", WorkerHandler::new(config, worker_state));
     mount.mount("/static", Static::new("public/static"));
     mount.mount("/", Static::new("public"));
 
+    let guard = ShutdownGuard::new();
+    let mut chain = Chain::new(mount);
+    chain.link_around(guard.clone());
+
     let server = config.server();
     let address = server.address();
     let port = server.port();
 
     println!("Listening on {}:{}...", address, port);
 
-    match Iron::new(mount).http((address, port)) {
-        Ok(_) => {}
-        Err(err) => error!("Failed to start HTTP server: {}", err),
+    match Iron::new(chain).http((address, port)) {
+        Ok(listening) => Some(Server { listening, guard }),
+        Err(err) => {
+            error!("Failed to start HTTP server: {}", err);
+
+            None
+        }
     }
 }
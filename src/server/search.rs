@@ -0,0 +1,153 @@
+use iron::middleware::Handler;
+use iron::status;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use serde_json;
+use serde_yaml;
+
+use config::Config;
+use database::Database;
+use database::SearchQuery;
+use database::SearchResult;
+use entity::Entry;
+use entity::Timestamp;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResponse {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    results: Option<Vec<SearchMatch>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+/// Entry found by search.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    member: String,
+    id: String,
+    published: Timestamp,
+    title: String,
+    link: String,
+    application: String,
+    snippet: String,
+}
+
+impl SearchResponse {
+    fn with_results(results: Vec<SearchMatch>) -> SearchResponse {
+        SearchResponse {
+            success: true,
+            results: Some(results),
+            message: None,
+        }
+    }
+
+    fn with_error(message: &str) -> SearchResponse {
+        SearchResponse {
+            success: false,
+            results: None,
+            message: Some(message.into()),
+        }
+    }
+}
+
+impl SearchMatch {
+    fn new(result: &SearchResult) -> Option<SearchMatch> {
+        let entry: Entry = match serde_yaml::from_str(result.data()) {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("Failed to deserialize entry: {}", err);
+
+                return None;
+            }
+        };
+
+        Some(SearchMatch {
+            member: result.author().into(),
+            id: entry.id().into(),
+            published: entry.published(),
+            title: entry.title_text(),
+            link: entry.alternate().into(),
+            application: entry.application().into(),
+            snippet: result.snippet().into(),
+        })
+    }
+}
+
+/// Searches stored activity. Words are given in query parameter `q`, results can be filtered
+/// with `member` (can be repeated), `from` and `to` (UNIX time), `application` and object
+/// `type` like `issue` and limited with `limit`.
+#[derive(Debug)]
+pub struct SearchHandler {
+    members: Vec<String>,
+    database: Database,
+}
+
+impl SearchHandler {
+    pub fn new(config: &Config, database: Database) -> SearchHandler {
+        SearchHandler {
+            members: config.members().into(),
+            database,
+        }
+    }
+
+    fn parse_query(&self, req: &Request) -> Result<SearchQuery, &'static str> {
+        let url = req.url.as_ref();
+        let mut text = String::new();
+        let mut members = Vec::new();
+        let mut start_date = None;
+        let mut end_date = None;
+        let mut application = None;
+        let mut object_type = None;
+        let mut limit = DEFAULT_LIMIT;
+
+        for (name, value) in url.query_pairs() {
+            match name.as_ref() {
+                "q" => text = value.into_owned(),
+                "member" => members.push(value.into_owned()),
+                "from" => start_date = Some(value.parse().map_err(|_| "Invalid `from` time")?),
+                "to" => end_date = Some(value.parse().map_err(|_| "Invalid `to` time")?),
+                "application" => application = Some(value.into_owned()),
+                "type" => object_type = Some(value.into_owned()),
+                "limit" => limit = value.parse().map_err(|_| "Invalid limit")?,
+                _ => {}
+            }
+        }
+
+        if text.trim().is_empty() {
+            return Err("Missing query text");
+        }
+
+        if !members.is_empty() {
+            members.retain(|member| self.members.contains(member));
+
+            if members.is_empty() {
+                return Err("Unknown member");
+            }
+        }
+
+        Ok(SearchQuery::new(&text, limit.min(MAX_LIMIT))
+            .with_members(&members)
+            .with_interval(start_date, end_date)
+            .with_application(application.as_ref())
+            .with_object_type(object_type.as_ref()))
+    }
+}
+
+impl Handler for SearchHandler {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        let query = try_msg!(self.parse_query(req), SearchResponse::with_error);
+        let results = match self.database.search(&query) {
+            Ok(results) => results,
+            Err(_) => return Ok(Response::with(status::InternalServerError)),
+        };
+
+        try_err!(SearchResponse::with_results(
+            results.iter().filter_map(SearchMatch::new).collect()
+        ))
+    }
+}
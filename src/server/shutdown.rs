@@ -0,0 +1,138 @@
+use iron::middleware::AroundMiddleware;
+use iron::middleware::Handler;
+use iron::status;
+use iron::IronError;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Rejects all incoming requests with `503 Service Unavailable` after server was stopped and
+/// keeps count of requests being handled, so stopping can wait for them.
+#[derive(Debug, Clone)]
+pub struct ShutdownGuard {
+    state: Arc<(Mutex<GuardState>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    stopped: bool,
+    active: usize,
+}
+
+impl ShutdownGuard {
+    pub fn new() -> ShutdownGuard {
+        ShutdownGuard {
+            state: Arc::new((Mutex::new(GuardState::default()), Condvar::new())),
+        }
+    }
+
+    /// Rejects new requests and waits up to given time for requests being handled. Returns
+    /// `false` if some requests did not finish in time.
+    pub fn stop(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let (_, ref finished) = *self.state;
+        let mut state = self.lock();
+
+        state.stopped = true;
+
+        while state.active > 0 {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return false;
+            }
+
+            state = finished
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+
+        true
+    }
+
+    /// Registers request unless server was stopped.
+    fn enter(&self) -> Option<ActiveRequest> {
+        let mut state = self.lock();
+
+        if state.stopped {
+            return None;
+        }
+
+        state.active += 1;
+
+        Some(ActiveRequest {
+            guard: self.clone(),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GuardState> {
+        self.state.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl AroundMiddleware for ShutdownGuard {
+    fn around(self, handler: Box<dyn Handler>) -> Box<dyn Handler> {
+        Box::new(GuardedHandler {
+            guard: self,
+            handler,
+        })
+    }
+}
+
+struct GuardedHandler {
+    guard: ShutdownGuard,
+    handler: Box<dyn Handler>,
+}
+
+impl Handler for GuardedHandler {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        let _active = match self.guard.enter() {
+            Some(active) => active,
+            None => {
+                return Err(IronError::new(ShuttingDown, status::ServiceUnavailable));
+            }
+        };
+
+        self.handler.handle(req)
+    }
+}
+
+/// Request being handled, unregistered when dropped, also when handler panics.
+struct ActiveRequest {
+    guard: ShutdownGuard,
+}
+
+impl Drop for ActiveRequest {
+    fn drop(&mut self) {
+        let mut state = self.guard.lock();
+
+        state.active -= 1;
+
+        if state.active == 0 {
+            (self.guard.state.1).notify_all();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ShuttingDown;
+
+impl Display for ShuttingDown {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "Server is shutting down")
+    }
+}
+
+impl Error for ShuttingDown {}
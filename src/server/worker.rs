@@ -0,0 +1,115 @@
+use iron::method::Method;
+use iron::middleware::Handler;
+use iron::status;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use serde_json;
+use std::io::Read;
+
+use config::Config;
+use worker::WorkerCommand;
+use worker::WorkerState;
+use worker::WorkerStatus;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerResult {
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<WorkerStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl WorkerResult {
+    fn with_status(status: WorkerStatus) -> WorkerResult {
+        WorkerResult {
+            success: true,
+            status: Some(status),
+            message: None,
+        }
+    }
+
+    fn with_message(message: &str) -> WorkerResult {
+        WorkerResult {
+            success: true,
+            status: None,
+            message: Some(message.into()),
+        }
+    }
+
+    fn with_error(message: &str) -> WorkerResult {
+        WorkerResult {
+            success: false,
+            status: None,
+            message: Some(message.into()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WorkerRequest {
+    member: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct WorkerHandler {
+    members: Vec<String>,
+    state: WorkerState,
+}
+
+impl WorkerHandler {
+    pub fn new(config: &Config, state: WorkerState) -> WorkerHandler {
+        WorkerHandler {
+            members: config.members().into(),
+            state,
+        }
+    }
+
+    fn trigger(&self, req: &mut Request) -> IronResult<Response> {
+        let mut body = String::new();
+
+        try_msg!(req.body.read_to_string(&mut body), |_| {
+            WorkerResult::with_error("Incomplete request")
+        });
+
+        let request: WorkerRequest = if body.trim().is_empty() {
+            WorkerRequest::default()
+        } else {
+            try_msg!(serde_json::from_str(&body), |_| WorkerResult::with_error(
+                "Invalid request, expected member name or empty body"
+            ))
+        };
+
+        if !self.state.is_alive() {
+            return try_err!(WorkerResult::with_error("Worker is not running"));
+        }
+
+        match request.member {
+            Some(member) => {
+                if !self.members.contains(&member) {
+                    return try_err!(WorkerResult::with_error("Unknown member"));
+                }
+
+                self.state.send_command(WorkerCommand::UpdateMember(member));
+
+                try_err!(WorkerResult::with_message("Member update scheduled"))
+            }
+            None => {
+                self.state.send_command(WorkerCommand::UpdateAll);
+
+                try_err!(WorkerResult::with_message("Update scheduled"))
+            }
+        }
+    }
+}
+
+impl Handler for WorkerHandler {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        match req.method {
+            Method::Get => try_err!(WorkerResult::with_status(self.state.status())),
+            Method::Post => self.trigger(req),
+            _ => Ok(Response::with(status::MethodNotAllowed)),
+        }
+    }
+}
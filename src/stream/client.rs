@@ -1,20 +1,30 @@
+use reqwest::header::ContentType;
 use reqwest::Certificate;
 use reqwest::Client;
+use reqwest::Response;
+use reqwest::Url;
 use std::fs::File;
+use std::io::Cursor;
 use std::io::Read;
+use std::io::Result as IoResult;
 
 use super::error::ActivityStreamsError;
 use super::error::ActivityStreamsResult;
+use super::recording::Recordings;
 
 use config::Config;
+use config::SourceConfig;
+use config::StreamsMode;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ActivityStreamsClient {
     client: Client,
     url: String,
     max_results: usize,
     username: String,
     password: Option<String>,
+    mode: StreamsMode,
+    recordings: Recordings,
 }
 
 impl ActivityStreamsClient {
@@ -49,26 +59,22 @@ impl ActivityStreamsClient {
             max_results: streams.max_results(),
             username: streams.username().into(),
             password: streams.password().cloned(),
+            mode: streams.mode(),
+            recordings: Recordings::new(streams.recordings()),
         }
     }
 
-    pub fn query(&self, user_name: &str) -> ActivityStreamsResult<String> {
-        let max_results = format!("{}", &self.max_results);
-        let streams_user = format!("user IS {}", user_name,);
-        let mut responce = self
-            .client
-            .get(&self.url)
-            .basic_auth(self.username.clone(), self.password.clone())
-            .query(&[("maxResults", &max_results), ("streams", &streams_user)])
-            .send()
-            .map_err(ActivityStreamsError::request_error)?;
-        let status = responce.status();
+    /// Returns client of additional source sharing connection settings and credentials.
+    pub fn with_source(mut self, source: &SourceConfig) -> ActivityStreamsClient {
+        self.url = source.url().into();
+        self.recordings = self.recordings.subdirectory(source.name());
+        self
+    }
 
-        if status.is_success() {
-            responce.text().map_err(ActivityStreamsError::request_error)
-        } else {
-            Err(ActivityStreamsError::status_not_success(status))
-        }
+    pub fn query(&self, user_name: &str) -> ActivityStreamsResult<StreamsResponse> {
+        let streams_user = format!("user IS {}", user_name);
+
+        self.send(user_name, &[("streams", &streams_user)])
     }
 
     pub fn query_between(
@@ -76,51 +82,121 @@ impl ActivityStreamsClient {
         user_name: &str,
         start_time: i64,
         end_time: i64,
-    ) -> ActivityStreamsResult<String> {
-        let max_results = format!("{}", &self.max_results);
-        let streams_user = format!("user IS {}", user_name,);
+    ) -> ActivityStreamsResult<StreamsResponse> {
+        let streams_user = format!("user IS {}", user_name);
         let streams_update = format!("update-date BETWEEN {} {}", start_time, end_time);
-        let mut responce = self
-            .client
-            .get(&self.url)
-            .basic_auth(self.username.clone(), self.password.clone())
-            .query(&[
-                ("maxResults", &max_results),
-                ("streams", &streams_user),
-                ("streams", &streams_update),
-            ])
-            .send()
-            .map_err(ActivityStreamsError::request_error)?;
-        let status = responce.status();
 
-        if status.is_success() {
-            responce.text().map_err(ActivityStreamsError::request_error)
-        } else {
-            Err(ActivityStreamsError::status_not_success(status))
-        }
+        self.send(
+            user_name,
+            &[("streams", &streams_user), ("streams", &streams_update)],
+        )
     }
 
-    pub fn query_after(&self, user_name: &str, start_time: i64) -> ActivityStreamsResult<String> {
-        let max_results = format!("{}", &self.max_results);
-        let streams_user = format!("user IS {}", user_name,);
+    pub fn query_after(
+        &self,
+        user_name: &str,
+        start_time: i64,
+    ) -> ActivityStreamsResult<StreamsResponse> {
+        let streams_user = format!("user IS {}", user_name);
         let streams_update = format!("update-date AFTER {}", start_time);
+
+        self.send(
+            user_name,
+            &[("streams", &streams_user), ("streams", &streams_update)],
+        )
+    }
+
+    /// Sends request with given streams filters or replays recorded response, depending on mode.
+    fn send(
+        &self,
+        user_name: &str,
+        streams: &[(&str, &String)],
+    ) -> ActivityStreamsResult<StreamsResponse> {
+        let max_results = format!("{}", &self.max_results);
+        let mut params = vec![("maxResults", &max_results)];
+        params.extend_from_slice(streams);
+
+        let url = Url::parse_with_params(&self.url, &params)
+            .map_err(ActivityStreamsError::invalid_url)?;
+
+        if self.mode == StreamsMode::Replay {
+            return match self.recordings.load(user_name, url.as_str()) {
+                Ok(Some(body)) => Ok(StreamsResponse::Recorded {
+                    body: Cursor::new(body),
+                    content_type: None,
+                }),
+                Ok(None) => Err(ActivityStreamsError::recording_not_found(url.as_str())),
+                Err(err) => Err(ActivityStreamsError::recording_error(err)),
+            };
+        }
+
         let mut responce = self
             .client
-            .get(&self.url)
+            .get(url.clone())
             .basic_auth(self.username.clone(), self.password.clone())
-            .query(&[
-                ("maxResults", &max_results),
-                ("streams", &streams_user),
-                ("streams", &streams_update),
-            ])
             .send()
             .map_err(ActivityStreamsError::request_error)?;
         let status = responce.status();
 
-        if status.is_success() {
-            responce.text().map_err(ActivityStreamsError::request_error)
+        if !status.is_success() {
+            return Err(ActivityStreamsError::status_not_success(status));
+        }
+
+        if self.mode == StreamsMode::Record {
+            let content_type = content_type(&responce);
+            let mut body = Vec::new();
+
+            responce
+                .read_to_end(&mut body)
+                .map_err(ActivityStreamsError::request_error)?;
+            self.recordings
+                .save(user_name, url.as_str(), &body)
+                .map_err(ActivityStreamsError::recording_error)?;
+
+            Ok(StreamsResponse::Recorded {
+                body: Cursor::new(body),
+                content_type,
+            })
         } else {
-            Err(ActivityStreamsError::status_not_success(status))
+            Ok(StreamsResponse::Live(Box::new(responce)))
+        }
+    }
+}
+
+/// Body of streams response, read from network or from recording.
+#[derive(Debug)]
+pub enum StreamsResponse {
+    Live(Box<Response>),
+    Recorded {
+        body: Cursor<Vec<u8>>,
+        content_type: Option<String>,
+    },
+}
+
+impl StreamsResponse {
+    /// Returns value of `Content-Type` header, replayed responses have no content type.
+    pub fn content_type(&self) -> Option<String> {
+        match self {
+            StreamsResponse::Live(ref response) => content_type(response),
+            StreamsResponse::Recorded {
+                ref content_type, ..
+            } => content_type.clone(),
         }
     }
 }
+
+impl Read for StreamsResponse {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        match self {
+            StreamsResponse::Live(ref mut response) => response.read(buf),
+            StreamsResponse::Recorded { ref mut body, .. } => body.read(buf),
+        }
+    }
+}
+
+fn content_type(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get::<ContentType>()
+        .map(|content_type| content_type.to_string())
+}
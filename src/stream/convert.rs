@@ -81,7 +81,13 @@ impl<'a> FeedToActivity<'a> {
         let mut result = HashMap::with_capacity(2);
 
         for activity in self.activities {
-            if is_entry_match(entry, activity.verbs(), activity.application()) {
+            if is_entry_match(
+                entry,
+                activity.verbs(),
+                activity.application(),
+                activity.categories(),
+                activity.links(),
+            ) {
                 if let Some(group) = get_entry_group(entry, activity.group()) {
                     result.insert(activity.key(), group);
                 }
@@ -93,7 +99,13 @@ impl<'a> FeedToActivity<'a> {
 
     fn should_ingore_entry(&self, entry: &Entry) -> bool {
         for ignore in self.ignore {
-            if is_entry_match(entry, ignore.verbs(), ignore.application()) {
+            if is_entry_match(
+                entry,
+                ignore.verbs(),
+                ignore.application(),
+                ignore.categories(),
+                ignore.links(),
+            ) {
                 return true;
             }
         }
@@ -136,66 +148,71 @@ where
 
 fn get_entry_group(entry: &Entry, group: MessageGroup) -> Option<String> {
     match group {
-        MessageGroup::TargetIssue | MessageGroup::TargetReview | MessageGroup::TargetPage => {
-            entry.target().map(|t| format!("{}", t))
+        MessageGroup::TargetIssue
+        | MessageGroup::TargetReview
+        | MessageGroup::TargetPage
+        | MessageGroup::TargetSprint
+        | MessageGroup::TargetBoard
+        | MessageGroup::TargetPullRequest
+        | MessageGroup::TargetBlogPost => entry.target().map(|t| format!("{}", t)),
+        MessageGroup::ObjectIssue => find_object(entry, |o| matches!(o, Object::Issue { .. })),
+        MessageGroup::ObjectReview => find_object(entry, |o| matches!(o, Object::Review { .. })),
+        MessageGroup::ObjectPage => find_object(entry, |o| matches!(o, Object::Page { .. })),
+        MessageGroup::ObjectAttachment => find_object(entry, |o| {
+            matches!(o, Object::Attachment { .. } | Object::File { .. })
+        }),
+        MessageGroup::ObjectSprint => find_object(entry, |o| matches!(o, Object::Sprint { .. })),
+        MessageGroup::ObjectBoard => find_object(entry, |o| matches!(o, Object::Board { .. })),
+        MessageGroup::ObjectCommit => find_object(entry, |o| {
+            matches!(o, Object::Commit { .. } | Object::Changeset { .. })
+        }),
+        MessageGroup::ObjectBuild => find_object(entry, |o| matches!(o, Object::Build { .. })),
+        MessageGroup::ObjectPullRequest => {
+            find_object(entry, |o| matches!(o, Object::PullRequest { .. }))
         }
-        MessageGroup::ObjectIssue => entry
-            .objects()
-            .iter()
-            .filter_map(|o| {
-                if let Object::Issue { .. } = o {
-                    Some(format!("{}", o))
-                } else {
-                    None
-                }
-            })
-            .next(),
-        MessageGroup::ObjectReview => entry
-            .objects()
-            .iter()
-            .filter_map(|o| {
-                if let Object::Review { .. } = o {
-                    Some(format!("{}", o))
-                } else {
-                    None
-                }
-            })
-            .next(),
-        MessageGroup::ObjectPage => entry
-            .objects()
-            .iter()
-            .filter_map(|o| {
-                if let Object::Page { .. } = o {
-                    Some(format!("{}", o))
-                } else {
-                    None
-                }
-            })
-            .next(),
-        MessageGroup::Content => entry.content().map(|t| find_link(t).into()),
+        MessageGroup::ObjectBlogPost => {
+            find_object(entry, |o| matches!(o, Object::BlogPost { .. }))
+        }
+        MessageGroup::Content => entry.content_text().map(|content| {
+            content
+                .links()
+                .iter()
+                .map(|link| link.text())
+                .find(|text| !text.is_empty())
+                .map(|text| text.into())
+                .unwrap_or_else(|| content.summary())
+        }),
+        MessageGroup::Project => entry.projects().first().map(|project| project.to_string()),
     }
 }
 
-fn find_link(text: &str) -> &str {
-    if let Some(end_index) = text.find("</a>") {
-        if let Some(start_index) = text[..end_index].rfind(">") {
-            &text[start_index + 1..end_index]
-        } else {
-            text
-        }
-    } else {
-        text
-    }
+/// Returns name of first entry object matching predicate.
+fn find_object<F>(entry: &Entry, predicate: F) -> Option<String>
+where
+    F: Fn(&Object) -> bool,
+{
+    entry
+        .objects()
+        .iter()
+        .filter(|o| predicate(o))
+        .map(|o| format!("{}", o))
+        .next()
 }
 
 fn is_entry_match(
     entry: &Entry,
     match_verbs: &[String],
     match_application: Option<&String>,
+    match_categories: &[String],
+    match_links: &[String],
 ) -> bool {
     let verbs = entry.verbs();
+    let has_categories = match_categories
+        .iter()
+        .all(|term| entry.categories().iter().any(|c| c.term() == term));
+    let has_links = match_links.iter().all(|rel| entry.link(rel).is_some());
 
-    if verbs == match_verbs {
+    if verbs == match_verbs && has_categories && has_links {
         if let Some(application) = match_application {
             if application == entry.application() {
                 true
@@ -3,6 +3,7 @@ use std::error::Error;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+use std::io::Error as IoError;
 
 pub type ActivityStreamsResult<T> = Result<T, ActivityStreamsError>;
 
@@ -15,6 +16,15 @@ pub enum ActivityStreamsError {
         status_code: u16,
         message: Option<&'static str>,
     },
+    InvalidUrl {
+        message: String,
+    },
+    RecordingError {
+        message: String,
+    },
+    RecordingNotFound {
+        url: String,
+    },
 }
 
 impl ActivityStreamsError {
@@ -33,6 +43,31 @@ impl ActivityStreamsError {
             message: status.canonical_reason(),
         }
     }
+
+    pub fn invalid_url<E>(error: E) -> ActivityStreamsError
+    where
+        E: Error,
+    {
+        warn!("Invalid streams URL: {}", error);
+
+        ActivityStreamsError::InvalidUrl {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn recording_error(error: IoError) -> ActivityStreamsError {
+        warn!("Failed to access recording: {}", error);
+
+        ActivityStreamsError::RecordingError {
+            message: format!("{}", error),
+        }
+    }
+
+    pub fn recording_not_found(url: &str) -> ActivityStreamsError {
+        warn!("Response of {} not recorded", url);
+
+        ActivityStreamsError::RecordingNotFound { url: url.into() }
+    }
 }
 
 impl Display for ActivityStreamsError {
@@ -44,6 +79,15 @@ impl Display for ActivityStreamsError {
             ActivityStreamsError::StatusNotSuccess { status_code, .. } => {
                 write!(f, "Status code {} not success", status_code)
             }
+            ActivityStreamsError::InvalidUrl { ref message } => {
+                write!(f, "Invalid URL: `{}`", message)
+            }
+            ActivityStreamsError::RecordingError { ref message } => {
+                write!(f, "Recording error: `{}`", message)
+            }
+            ActivityStreamsError::RecordingNotFound { ref url } => {
+                write!(f, "Response of `{}` not recorded", url)
+            }
         }
     }
 }
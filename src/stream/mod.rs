@@ -1,6 +1,7 @@
 mod client;
 mod convert;
 mod error;
+mod recording;
 
 pub use self::client::ActivityStreamsClient;
 pub use self::convert::FeedToActivity;
@@ -0,0 +1,129 @@
+use reqwest::Url;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+/// Streams filter depending on stored activity, ignored when recording is matched to request.
+const UPDATE_DATE_FILTER: &str = "update-date ";
+
+/// Directory of recorded streams responses. Responses of every member are numbered in order of
+/// recording and stored as `<member>-<number>.body` next to `<member>-<number>.url` containing
+/// request URL, existing recordings are never overwritten. Replay returns recordings of member
+/// in the same order, so recorded rounds can be replayed into any database although update
+/// dates of requests differ.
+#[derive(Debug, Clone)]
+pub struct Recordings {
+    directory: PathBuf,
+    replayed: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl Recordings {
+    pub fn new(directory: &str) -> Recordings {
+        Recordings {
+            directory: PathBuf::from(directory),
+            replayed: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns recordings stored in subdirectory of given name.
+    pub fn subdirectory(&self, name: &str) -> Recordings {
+        Recordings {
+            directory: self.directory.join(name),
+            replayed: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Stores response body of given request URL as next recording of member, creates directory
+    /// if needed.
+    pub fn save(&self, member: &str, url: &str, body: &[u8]) -> IoResult<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        let mut number = 1;
+        let mut file = loop {
+            let path = self.path(member, number, "body");
+
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(file) => break file,
+                Err(ref err) if err.kind() == ErrorKind::AlreadyExists => number += 1,
+                Err(err) => return Err(err),
+            }
+        };
+
+        file.write_all(body)?;
+        fs::write(self.path(member, number, "url"), url)?;
+
+        debug!("Recorded response of {} as {} of {}", url, number, member);
+
+        Ok(())
+    }
+
+    /// Loads next recorded response of member. Returns `None` if all recordings of member were
+    /// replayed, recording made for other request is an error.
+    pub fn load(&self, member: &str, url: &str) -> IoResult<Option<Vec<u8>>> {
+        let number = {
+            let mut replayed = self.replayed.lock().unwrap_or_else(PoisonError::into_inner);
+            let number = replayed.entry(member.into()).or_insert(0);
+
+            *number += 1;
+            *number
+        };
+        let mut file = match File::open(self.path(member, number, "body")) {
+            Ok(file) => file,
+            Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let recorded_url = fs::read_to_string(self.path(member, number, "url"))?;
+
+        if request_key(&recorded_url) != request_key(url) {
+            return Err(IoError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "recording {} of {} was made for {}",
+                    number, member, recorded_url
+                ),
+            ));
+        }
+
+        let mut body = Vec::new();
+
+        file.read_to_end(&mut body)?;
+
+        debug!(
+            "Replaying response of {} from {} of {}",
+            url, number, member
+        );
+
+        Ok(Some(body))
+    }
+
+    fn path(&self, member: &str, number: usize, extension: &str) -> PathBuf {
+        self.directory
+            .join(format!("{}-{:06}.{}", member, number, extension))
+    }
+}
+
+/// Returns request URL without update date filters.
+fn request_key(url: &str) -> String {
+    let mut url = match Url::parse(url.trim()) {
+        Ok(url) => url,
+        Err(_) => return url.trim().into(),
+    };
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, value)| name != "streams" || !value.starts_with(UPDATE_DATE_FILTER))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.as_str().into()
+}
@@ -0,0 +1,175 @@
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+
+use super::*;
+
+use command;
+use command::CommandError;
+use database::Database;
+use database::EntryData;
+
+fn archive_path(name: &str) -> String {
+    env::temp_dir()
+        .join(format!("team-activity-{}-{}.jsonl.gz", name, process::id()))
+        .to_str()
+        .unwrap()
+        .into()
+}
+
+fn entry(id: &str, updated: i64) -> EntryData {
+    EntryData::new(id, 1_000, updated, &format!("{} revision {}", id, updated))
+}
+
+#[test]
+fn export_and_restore_keep_entries_and_revisions() {
+    let source_database = TempDatabase::new("export");
+    let target_database = TempDatabase::new("restore");
+    let source_config = test_config("http://127.0.0.1:1/streams", &source_database, 25);
+    let target_config = test_config("http://127.0.0.1:1/streams", &target_database, 25);
+    let source = Database::new(&source_config).unwrap();
+    let target = Database::new(&target_config).unwrap();
+    let path = archive_path("archive");
+
+    source
+        .save_entries("jdoe", &[entry("a", 1), entry("b", 1)])
+        .unwrap();
+    source.save_entries("jdoe", &[entry("a", 2)]).unwrap();
+    source.save_entries("asmith", &[entry("c", 1)]).unwrap();
+    source.save_entries("asmith", &[entry("c", 3)]).unwrap();
+
+    let exported = command::export(&source, &path).unwrap();
+
+    assert_eq!(exported.entries(), 3);
+    assert_eq!(exported.revisions(), 2);
+
+    let restored = command::restore(&target, &path).unwrap();
+
+    assert_eq!(restored, exported);
+    assert_eq!(target.entry_count().unwrap(), 3);
+    assert_eq!(target.revision_count().unwrap(), 2);
+    assert_eq!(target.last_published("asmith").unwrap(), Some(1_000));
+
+    let entries = target.entries_after("", 10).unwrap();
+    let (ref author, ref data) = entries[2];
+
+    assert_eq!(author, "asmith");
+    assert_eq!(data.id(), "c");
+    assert_eq!(data.updated(), 3);
+    assert_eq!(data.data(), "c revision 3");
+
+    let revisions = target.revisions_after("", 0, 10).unwrap();
+
+    assert_eq!(revisions[0].id(), "a");
+    assert_eq!(revisions[0].data(), "a revision 1");
+    assert_eq!(revisions[1].id(), "c");
+    assert_eq!(revisions[1].updated(), 1);
+
+    match command::restore(&target, &path) {
+        Err(CommandError::DatabaseNotEmpty) => {}
+        result => panic!("Unexpected restore result {:?}", result),
+    }
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn restore_rejects_truncated_archive() {
+    let temp_database = TempDatabase::new("truncated");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let path = archive_path("truncated");
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+
+    writeln!(encoder, r#"{{"type":"header","version":1,"created":0}}"#).unwrap();
+    writeln!(
+        encoder,
+        r#"{{"type":"entry","id":"a","author":"jdoe","published":1,"updated":1,"data":"a"}}"#
+    )
+    .unwrap();
+    encoder.finish().unwrap();
+
+    match command::restore(&database, &path) {
+        Err(CommandError::InvalidArchive { .. }) => {}
+        result => panic!("Unexpected restore result {:?}", result),
+    }
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn restore_of_truncated_archive_leaves_database_empty() {
+    let temp_database = TempDatabase::new("truncated-large");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let path = archive_path("truncated-large");
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+
+    // More records than restore would store at once.
+    writeln!(encoder, r#"{{"type":"header","version":1,"created":0}}"#).unwrap();
+
+    for index in 0..1_200 {
+        writeln!(
+            encoder,
+            r#"{{"type":"entry","id":"e{}","author":"jdoe","published":1,"updated":1,"data":"e"}}"#,
+            index
+        )
+        .unwrap();
+    }
+
+    encoder.finish().unwrap();
+
+    match command::restore(&database, &path) {
+        Err(CommandError::InvalidArchive { .. }) => {}
+        result => panic!("Unexpected restore result {:?}", result),
+    }
+
+    assert_eq!(database.entry_count().unwrap(), 0);
+    assert_eq!(database.revision_count().unwrap(), 0);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn restore_checks_header_after_blank_lines() {
+    let temp_database = TempDatabase::new("blank-lines");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let write_archive = |path: &str, header: bool| {
+        let mut encoder = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+
+        writeln!(encoder).unwrap();
+
+        if header {
+            writeln!(encoder, r#"{{"type":"header","version":1,"created":0}}"#).unwrap();
+        }
+
+        writeln!(
+            encoder,
+            r#"{{"type":"entry","id":"a","author":"jdoe","published":1,"updated":1,"data":"a"}}"#
+        )
+        .unwrap();
+        writeln!(encoder, r#"{{"type":"footer","entries":1,"revisions":0}}"#).unwrap();
+        encoder.finish().unwrap();
+    };
+    let headerless = archive_path("headerless");
+    let with_header = archive_path("blank-lines");
+
+    write_archive(&headerless, false);
+    write_archive(&with_header, true);
+
+    match command::restore(&database, &headerless) {
+        Err(CommandError::InvalidArchive { .. }) => {}
+        result => panic!("Unexpected restore result {:?}", result),
+    }
+
+    assert_eq!(
+        command::restore(&database, &with_header).unwrap().entries(),
+        1
+    );
+
+    fs::remove_file(&headerless).unwrap();
+    fs::remove_file(&with_header).unwrap();
+}
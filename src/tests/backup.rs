@@ -0,0 +1,132 @@
+use chrono::DateTime;
+use chrono::Utc;
+use std::fs;
+
+use super::*;
+
+use backup::BackupWorker;
+use database::Database;
+use database::EntryData;
+
+fn time(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value).unwrap().into()
+}
+
+fn file_names(directory: &PathBuf) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+
+    names.sort();
+
+    names
+}
+
+#[test]
+fn backup_rotates_snapshots_and_prunes_old_entries() {
+    let temp_database = TempDatabase::new("backup");
+    let directory = env::temp_dir().join(format!("team-activity-backups-{}", process::id()));
+    let backup = format!(
+        "backup:\n  directory: \"{}\"\n  daily: 2\n  weekly: 2\n  retention_days: 30",
+        directory.to_str().unwrap()
+    );
+    let config = test_config_with(
+        "http://127.0.0.1:1/streams",
+        &temp_database,
+        "max_results: 25",
+        &backup,
+    );
+    let database = Database::new(&config).unwrap();
+    let worker = BackupWorker::new(config.backup().unwrap(), database.clone());
+    let old = time("2018-01-01T00:00:00Z").timestamp();
+    let recent = time("2018-03-01T00:00:00Z").timestamp();
+
+    database
+        .save_entries(
+            "jdoe",
+            &[
+                EntryData::new("old", old, old, "old"),
+                EntryData::new("recent", recent, recent, "recent"),
+            ],
+        )
+        .unwrap();
+
+    for day in &["01", "02", "03", "09", "10", "17"] {
+        worker
+            .backup(time(&format!("2018-03-{}T12:00:00Z", day)))
+            .unwrap();
+    }
+
+    assert_eq!(
+        file_names(&directory),
+        vec![
+            "daily-2018-03-10.sqlite",
+            "daily-2018-03-17.sqlite",
+            "weekly-2018-03-09.sqlite",
+            "weekly-2018-03-17.sqlite",
+        ]
+    );
+    assert_eq!(database.entry_count().unwrap(), 1);
+    assert_eq!(database.last_published("jdoe").unwrap(), Some(recent));
+
+    let snapshot_database = TempDatabase {
+        path: directory.join("weekly-2018-03-09.sqlite"),
+    };
+    let snapshot_config = test_config("http://127.0.0.1:1/streams", &snapshot_database, 25);
+    let snapshot = Database::new(&snapshot_config).unwrap();
+
+    assert_eq!(snapshot.entry_count().unwrap(), 1);
+
+    // Newest entry of member is kept after retention period, updates continue from it.
+    worker.backup(time("2018-06-01T12:00:00Z")).unwrap();
+
+    assert_eq!(database.entry_count().unwrap(), 1);
+    assert_eq!(database.last_published("jdoe").unwrap(), Some(recent));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn backup_prunes_old_entries_without_snapshot() {
+    let temp_database = TempDatabase::new("backup-retention");
+    // Database file can not be used as backup directory, so snapshot fails.
+    let backup = format!(
+        "backup:\n  directory: \"{}\"\n  retention_days: 30",
+        temp_database.path()
+    );
+    let config = test_config_with(
+        "http://127.0.0.1:1/streams",
+        &temp_database,
+        "max_results: 25",
+        &backup,
+    );
+    let database = Database::new(&config).unwrap();
+    let worker = BackupWorker::new(config.backup().unwrap(), database.clone());
+    let old = time("2018-01-01T00:00:00Z").timestamp();
+    let recent = time("2018-03-01T00:00:00Z").timestamp();
+
+    database
+        .save_entries(
+            "jdoe",
+            &[
+                EntryData::new("old", old, old, "old"),
+                EntryData::new("recent", recent, recent, "recent"),
+            ],
+        )
+        .unwrap();
+
+    assert!(worker.backup(time("2018-03-10T12:00:00Z")).is_err());
+    assert_eq!(database.entry_count().unwrap(), 1);
+
+    database
+        .save_entries("jdoe", &[EntryData::new("old", old, old, "old")])
+        .unwrap();
+
+    // Only retention is applied with snapshots disabled.
+    let worker = worker.with_snapshots(false);
+
+    worker.backup(time("2018-03-10T12:00:00Z")).unwrap();
+
+    assert_eq!(database.entry_count().unwrap(), 1);
+}
@@ -0,0 +1,360 @@
+use chrono::DateTime;
+use reqwest::Client;
+use serde_json;
+use serde_json::Value;
+
+use super::mock_streams::MockStreams;
+use super::mock_streams::StreamsQuery;
+use super::*;
+
+use database::Database;
+use entity;
+use entity::Entry;
+use entity::FeedFormat;
+use server;
+use stream::ActivityStreamsClient;
+use stream::ActivityStreamsResult;
+use worker::Worker;
+use worker::WorkerCommand;
+use worker::WorkerRunState;
+use worker::WorkerState;
+
+/// 2018-03-14T00:00:00Z
+const ACTIVITY_DATE: i64 = 1_520_985_600;
+
+fn millis(value: &str) -> i64 {
+    DateTime::parse_from_rfc3339(value)
+        .unwrap()
+        .timestamp_millis()
+}
+
+fn entry_ids<R>(response: ActivityStreamsResult<R>) -> Vec<String>
+where
+    R: ::std::io::Read,
+{
+    let entries: Vec<Entry> = entity::read_entries(FeedFormat::Atom, response.unwrap())
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    entries.iter().map(|entry| entry.id().to_string()).collect()
+}
+
+fn query(user: &str, after: Option<i64>, before: Option<i64>, max_results: usize) -> StreamsQuery {
+    StreamsQuery {
+        user: user.into(),
+        after,
+        before,
+        max_results: Some(max_results),
+    }
+}
+
+#[test]
+fn client_queries_are_filtered_by_mock_streams() {
+    let streams = MockStreams::start(&[JIRA, BITBUCKET]);
+    let database = TempDatabase::new("client");
+    let client = ActivityStreamsClient::new(&test_config(&streams.url(), &database, 25));
+    let limited_client = ActivityStreamsClient::new(&test_config(&streams.url(), &database, 1));
+    let comment = "urn:uuid:3b8e7f62-1d4c-3a9e-b0c2-5e6f7a8b9c01";
+    let pull_request = "urn:uuid:0a1b2c3d-4e5f-3a6b-7c8d-9e0f1a2b3c41";
+    let push = "urn:uuid:3d4e5f6a-7b8c-3d9e-0f1a-2b3c4d5e6f71";
+    let start = millis("2018-03-14T07:00:00Z");
+    let end = millis("2018-03-14T08:00:00Z");
+    let after = millis("2018-03-14T09:12:00Z");
+
+    assert_eq!(entry_ids(client.query("jdoe")).len(), 4);
+    assert_eq!(entry_ids(limited_client.query("jdoe")), vec![comment]);
+    assert_eq!(
+        entry_ids(client.query_between("jdoe", start, end)),
+        vec![pull_request, push]
+    );
+    assert_eq!(entry_ids(client.query_after("jdoe", after)), vec![comment]);
+    assert!(entry_ids(client.query_after("asmith", after)).is_empty());
+
+    let response = client.query("nobody").unwrap();
+
+    assert_eq!(
+        response.content_type(),
+        Some("application/atom+xml".to_string())
+    );
+    assert!(entry_ids(Ok(response)).is_empty());
+
+    assert_eq!(
+        streams.queries(),
+        vec![
+            query("jdoe", None, None, 25),
+            query("jdoe", None, None, 1),
+            query("jdoe", Some(start), Some(end), 25),
+            query("jdoe", Some(after), None, 25),
+            query("asmith", Some(after), None, 25),
+            query("nobody", None, None, 25),
+        ]
+    );
+}
+
+#[test]
+fn worker_stores_activity_served_by_activity_handler() {
+    let streams = MockStreams::start(&[JIRA, BITBUCKET]);
+    let temp_database = TempDatabase::new("end-to-end");
+    let config = test_config(&streams.url(), &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let state = WorkerState::new(config.members());
+    let worker = Worker::new(&config, database.clone(), state.clone()).unwrap();
+    let join_worker = worker.start();
+    let is_idle_after = |n_queries: usize| {
+        streams.queries().len() >= n_queries && state.status().state() == WorkerRunState::Idle
+    };
+
+    wait_until("first worker round", || is_idle_after(2));
+
+    assert_eq!(
+        streams.queries(),
+        vec![
+            query("jdoe", None, None, 25),
+            query("asmith", None, None, 25)
+        ]
+    );
+    assert_eq!(
+        database
+            .published_between("jdoe", ACTIVITY_DATE, ACTIVITY_DATE + 24 * 60 * 60)
+            .unwrap()
+            .len(),
+        4
+    );
+    assert_eq!(
+        database
+            .published_between("asmith", ACTIVITY_DATE - 24 * 60 * 60, ACTIVITY_DATE)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    state.send_command(WorkerCommand::UpdateAll);
+
+    wait_until("second worker round", || is_idle_after(4));
+
+    let pull_interval = 3600 * 1000;
+
+    assert_eq!(
+        streams.queries()[2..].to_vec(),
+        vec![
+            query(
+                "jdoe",
+                Some(millis("2018-03-14T09:14:31Z") - pull_interval),
+                None,
+                25
+            ),
+            query(
+                "asmith",
+                Some(millis("2018-03-13T17:02:11Z") - pull_interval),
+                None,
+                25
+            ),
+        ]
+    );
+
+    let server = server::start(&config, database, state.clone()).unwrap();
+    let url = format!("http://{}/api/v1/activity", server.address());
+    let mut response = Client::new()
+        .post(&url)
+        .body(format!("{}", ACTIVITY_DATE))
+        .send()
+        .unwrap();
+    let result: Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+
+    assert!(server.stop(Duration::from_secs(5)));
+    state.stop();
+    join_worker.join().unwrap();
+
+    assert_eq!(result["success"], Value::Bool(true));
+
+    let mut activity: Vec<String> = result["activity"]["jdoe"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|status| status.as_str().unwrap().to_string())
+        .collect();
+
+    activity.sort();
+
+    assert_eq!(
+        activity,
+        vec![
+            "Created issue, Discussed issue - PROJ-101",
+            "Opened pull request - #42: PROJ-101 Refresh expired session",
+        ]
+    );
+    assert!(result["activity"].get("asmith").is_none());
+}
+
+#[test]
+fn worker_does_not_store_part_of_broken_response() {
+    let streams = MockStreams::start(&[JIRA, BITBUCKET]);
+    let temp_database = TempDatabase::new("broken-response");
+    let config = test_config(&streams.url(), &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let state = WorkerState::new(config.members());
+
+    streams.set_truncated(true);
+
+    let worker = Worker::new(&config, database.clone(), state.clone()).unwrap();
+    let join_worker = worker.start();
+    let is_idle_after = |n_queries: usize| {
+        streams.queries().len() >= n_queries && state.status().state() == WorkerRunState::Idle
+    };
+
+    wait_until("round with broken responses", || is_idle_after(2));
+
+    assert_eq!(database.entry_count().unwrap(), 0);
+
+    streams.set_truncated(false);
+    state.send_command(WorkerCommand::UpdateAll);
+
+    wait_until("round with complete responses", || is_idle_after(4));
+
+    state.stop();
+    join_worker.join().unwrap();
+
+    assert_eq!(
+        streams.queries()[2..].to_vec(),
+        vec![
+            query("jdoe", None, None, 25),
+            query("asmith", None, None, 25)
+        ]
+    );
+    assert_eq!(database.entry_count().unwrap(), 5);
+}
+
+#[test]
+fn worker_updates_every_source_from_own_position() {
+    let jira = MockStreams::start(&[JIRA]);
+    let bitbucket = MockStreams::start(&[BITBUCKET]);
+    let temp_database = TempDatabase::new("sources");
+    let sources = format!(
+        "max_results: 25\n  sources:\n    - name: bitbucket\n      url: \"{}\"\n      schedule: [ \"0 * * * *\" ]",
+        bitbucket.url()
+    );
+    let config = test_config_with(
+        &jira.url(),
+        &temp_database,
+        &sources,
+        "revision_lookback: 86400",
+    );
+    let database = Database::new(&config).unwrap();
+    let state = WorkerState::new(config.members());
+    let worker = Worker::new(&config, database.clone(), state.clone()).unwrap();
+    let join_worker = worker.start();
+    let is_idle_after = |n_queries: usize| {
+        jira.queries().len() + bitbucket.queries().len() >= n_queries
+            && state.status().state() == WorkerRunState::Idle
+    };
+
+    wait_until("first worker round", || is_idle_after(4));
+
+    assert_eq!(database.entry_count().unwrap(), 5);
+
+    state.send_command(WorkerCommand::UpdateAll);
+
+    wait_until("second worker round", || is_idle_after(8));
+
+    state.stop();
+    join_worker.join().unwrap();
+
+    let revision_lookback = 24 * 3600 * 1000;
+
+    // Newer Jira entries of member do not move position of Bitbucket.
+    assert_eq!(
+        jira.queries()[2..].to_vec(),
+        vec![
+            query(
+                "jdoe",
+                Some(millis("2018-03-14T09:14:31Z") - revision_lookback),
+                None,
+                25
+            ),
+            query(
+                "asmith",
+                Some(millis("2018-03-13T17:02:11Z") - revision_lookback),
+                None,
+                25
+            ),
+        ]
+    );
+    assert_eq!(
+        bitbucket.queries()[2..].to_vec(),
+        vec![
+            query(
+                "jdoe",
+                Some(millis("2018-03-14T07:30:00Z") - revision_lookback),
+                None,
+                25
+            ),
+            query("asmith", None, None, 25),
+        ]
+    );
+}
+
+#[test]
+fn client_replays_recorded_responses() {
+    let streams = MockStreams::start(&[JIRA, BITBUCKET]);
+    let database = TempDatabase::new("recordings");
+    let directory = env::temp_dir().join(format!("team-activity-recordings-{}", process::id()));
+    let streams_options = |mode: &str| {
+        format!(
+            "mode: {}\n  recordings: \"{}\"",
+            mode,
+            directory.to_str().unwrap()
+        )
+    };
+    let recording_client = ActivityStreamsClient::new(&test_config_with(
+        &streams.url(),
+        &database,
+        &streams_options("Record"),
+        "",
+    ));
+    let replay_client = ActivityStreamsClient::new(&test_config_with(
+        &streams.url(),
+        &database,
+        &streams_options("Replay"),
+        "",
+    ));
+    let after = millis("2018-03-14T09:12:00Z");
+    let all_entries = entry_ids(recording_client.query("jdoe"));
+    let new_entries = entry_ids(recording_client.query_after("jdoe", after));
+
+    assert_eq!(all_entries.len(), 4);
+    assert_eq!(new_entries.len(), 1);
+
+    // Recordings are replayed in recorded order, update date depends on replayed database.
+    assert_eq!(entry_ids(replay_client.query("jdoe")), all_entries);
+    assert_eq!(
+        entry_ids(replay_client.query_after("jdoe", after - 60_000)),
+        new_entries
+    );
+    assert!(replay_client.query("jdoe").is_err());
+    assert!(replay_client.query("asmith").is_err());
+    assert_eq!(streams.queries().len(), 2);
+
+    // Same request recorded again does not replace first recording.
+    assert_eq!(entry_ids(recording_client.query("jdoe")), all_entries);
+    assert!(directory.join("jdoe-000001.body").exists());
+    assert!(directory.join("jdoe-000003.body").exists());
+
+    // Recording made for other request is not replayed.
+    fs::write(
+        directory.join("jdoe-000001.url"),
+        format!("{}?maxResults=25&streams=user+IS+asmith", streams.url()),
+    )
+    .unwrap();
+
+    let other_replay_client = ActivityStreamsClient::new(&test_config_with(
+        &streams.url(),
+        &database,
+        &streams_options("Replay"),
+        "",
+    ));
+
+    assert!(other_replay_client.query("jdoe").is_err());
+
+    fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,72 @@
+use reqwest::Client;
+use serde_json;
+use serde_json::Value;
+use sqlite;
+
+use super::*;
+
+use command;
+use database::Database;
+use server;
+use worker::WorkerState;
+
+#[test]
+fn feed_handler_skips_entries_which_cannot_be_read() {
+    let temp_database = TempDatabase::new("feed-handler");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let paths = vec![
+        "fixtures/jira.xml".to_string(),
+        "fixtures/bitbucket.xml".to_string(),
+    ];
+
+    command::import(&config, &database, &paths).unwrap();
+
+    // 2018-03-15T00:00:00Z, newer than all imported entries.
+    sqlite::open(temp_database.path())
+        .unwrap()
+        .execute(
+            "INSERT INTO entry ( id, author, published, updated, data ) VALUES ( 'broken', 'jdoe', 1521072000, 1521072000, 'not an entry' )",
+        )
+        .unwrap();
+
+    let state = WorkerState::new(config.members());
+    let server = server::start(&config, database, state).unwrap();
+    let url = format!("http://{}/api/v1/feed/json", server.address());
+    let feed = |query: &[(&str, &str)]| -> (u16, Option<Value>) {
+        let mut response = Client::new()
+            .get(&url)
+            .query(&[("from", "0"), ("to", "2000000000")])
+            .query(query)
+            .send()
+            .unwrap();
+        let status = response.status().as_u16();
+
+        (status, serde_json::from_str(&response.text().unwrap()).ok())
+    };
+
+    let all = feed(&[]);
+    let member = feed(&[("member", "asmith")]);
+    let application = feed(&[("application", "com.atlassian.bitbucket")]);
+    let unknown_member = feed(&[("member", "nobody")]);
+    let team = feed(&[("member", "jdoe"), ("member", "asmith")]);
+    let overflowing_page = feed(&[("page", "400000000000000000")]);
+
+    assert!(server.stop(Duration::from_secs(5)));
+
+    let items = |(status, feed): &(u16, Option<Value>)| -> usize {
+        assert_eq!(*status, 200);
+
+        feed.as_ref().unwrap()["orderedItems"]
+            .as_array()
+            .unwrap()
+            .len()
+    };
+
+    assert_eq!(items(&all), 5);
+    assert_eq!(items(&member), 1);
+    assert_eq!(items(&application), 2);
+    assert_eq!(items(&unknown_member), 0);
+    assert_eq!(items(&team), 5);
+    assert_eq!(overflowing_page.0, 400);
+}
@@ -0,0 +1,50 @@
+use std::fs;
+
+use super::*;
+
+use command;
+use database::Database;
+
+#[test]
+fn import_stores_entries_of_members_once() {
+    let temp_database = TempDatabase::new("import");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let directory = env::temp_dir().join(format!("team-activity-import-{}", process::id()));
+
+    fs::create_dir_all(&directory).unwrap();
+
+    for name in &["jira.xml", "unknown_object.xml", "malformed.xml"] {
+        fs::copy(format!("fixtures/{}", name), directory.join(name)).unwrap();
+    }
+
+    fs::write(directory.join("notes.txt"), "not a feed").unwrap();
+
+    let paths = vec![directory.to_str().unwrap().to_string()];
+    let stats = command::import(&config, &database, &paths).unwrap();
+
+    assert_eq!(stats.files(), 2);
+    assert_eq!(stats.failed_files(), 1);
+    assert_eq!(stats.entries(), 4);
+    assert_eq!(stats.skipped(), 1);
+    assert_eq!(stats.saved().inserted(), 4);
+    assert_eq!(stats.saved().updated(), 0);
+
+    let paths = vec![
+        directory.join("jira.xml").to_str().unwrap().to_string(),
+        directory.join("missing.xml").to_str().unwrap().to_string(),
+    ];
+    let stats = command::import(&config, &database, &paths).unwrap();
+
+    assert_eq!(stats.files(), 1);
+    assert_eq!(stats.failed_files(), 1);
+    assert_eq!(stats.entries(), 3);
+    assert_eq!(stats.saved().inserted(), 0);
+    assert_eq!(stats.saved().updated(), 0);
+    assert_eq!(
+        database.last_published("asmith").unwrap(),
+        Some(1_520_960_531)
+    );
+
+    fs::remove_dir_all(&directory).unwrap();
+}
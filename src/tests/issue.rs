@@ -0,0 +1,59 @@
+use reqwest::Client;
+use serde_json;
+use serde_json::Value;
+
+use super::*;
+
+use command;
+use database::Database;
+use server;
+use worker::WorkerState;
+
+#[test]
+fn issue_handler_returns_timeline_of_issue() {
+    let temp_database = TempDatabase::new("issue-handler");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let paths = vec![
+        "fixtures/jira.xml".to_string(),
+        "fixtures/bitbucket.xml".to_string(),
+    ];
+
+    command::import(&config, &database, &paths).unwrap();
+
+    let state = WorkerState::new(config.members());
+    let server = server::start(&config, database, state).unwrap();
+    let issue = |path: &str| -> Value {
+        let url = format!("http://{}/api/v1/issue{}", server.address(), path);
+        let mut response = Client::new().get(&url).send().unwrap();
+
+        serde_json::from_str(&response.text().unwrap()).unwrap()
+    };
+
+    let timeline = issue("/PROJ-101");
+    let unknown = issue("/PROJ-1");
+    let invalid = issue("/proj-101");
+    let missing = issue("");
+
+    assert!(server.stop(Duration::from_secs(5)));
+
+    assert_eq!(timeline["success"], Value::Bool(true));
+    assert_eq!(timeline["issue_key"], "PROJ-101");
+
+    let entries = timeline["entries"].as_array().unwrap();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["member"], "jdoe");
+    assert_eq!(
+        entries[0]["title"],
+        "John Doe created PROJ-101 - Login fails with expired session"
+    );
+    assert_eq!(
+        entries[1]["title"],
+        "John Doe commented on PROJ-101 - Login fails with expired session"
+    );
+    assert!(entries[1]["content"].is_string());
+    assert!(unknown["entries"].as_array().unwrap().is_empty());
+    assert_eq!(invalid["message"], "Invalid issue key");
+    assert_eq!(missing["message"], "Missing issue key");
+}
@@ -0,0 +1,189 @@
+use chrono::Utc;
+use iron::middleware::Handler;
+use iron::mime::Mime;
+use iron::status;
+use iron::Iron;
+use iron::IronResult;
+use iron::Listening;
+use iron::Request;
+use iron::Response;
+use std::cmp::Reverse;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use entity;
+use entity::Entry;
+use entity::Feed;
+
+const STREAMS_PATH: &str = "/plugins/servlet/streams";
+const UTC_OFFSET: &str = "+0000";
+
+/// Query received by mock server. Update dates are in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamsQuery {
+    pub user: String,
+    pub after: Option<i64>,
+    pub before: Option<i64>,
+    pub max_results: Option<usize>,
+}
+
+/// In-process activity streams server serving canned entries. Supports `user IS`,
+/// `update-date AFTER` and `update-date BETWEEN` filters and `maxResults`, requests without
+/// user filter are rejected.
+pub struct MockStreams {
+    listening: Listening,
+    queries: Arc<Mutex<Vec<StreamsQuery>>>,
+    truncated: Arc<AtomicBool>,
+}
+
+impl MockStreams {
+    /// Starts server on free local port serving entries from given Atom feeds.
+    pub fn start(feeds: &[&[u8]]) -> MockStreams {
+        let mut entries = Vec::new();
+
+        for feed in feeds {
+            let feed = entity::read(*feed).expect("Failed to read fixture feed");
+
+            entries.extend(feed.entries().iter().cloned());
+        }
+
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let truncated = Arc::new(AtomicBool::new(false));
+        let handler = StreamsHandler {
+            entries,
+            queries: queries.clone(),
+            truncated: truncated.clone(),
+        };
+        let listening = Iron::new(handler)
+            .http(("127.0.0.1", 0))
+            .expect("Failed to start mock streams server");
+
+        MockStreams {
+            listening,
+            queries,
+            truncated,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}{}", self.listening.socket, STREAMS_PATH)
+    }
+
+    /// Returns queries received so far in order of arrival.
+    pub fn queries(&self) -> Vec<StreamsQuery> {
+        self.queries.lock().unwrap().clone()
+    }
+
+    /// Makes server send only first half of following responses, like broken connection.
+    pub fn set_truncated(&self, truncated: bool) {
+        self.truncated.store(truncated, Ordering::SeqCst);
+    }
+}
+
+impl Drop for MockStreams {
+    fn drop(&mut self) {
+        let _ = self.listening.close();
+    }
+}
+
+struct StreamsHandler {
+    entries: Vec<Entry>,
+    queries: Arc<Mutex<Vec<StreamsQuery>>>,
+    truncated: Arc<AtomicBool>,
+}
+
+impl StreamsHandler {
+    fn entries(&self, query: &StreamsQuery) -> Vec<Entry> {
+        let mut result: Vec<Entry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.author().username() == Some(query.user.as_str()))
+            .filter(|entry| {
+                let updated = entry.updated().timestamp_millis();
+
+                query.after.iter().all(|&after| updated > after)
+                    && query.before.iter().all(|&before| updated < before)
+            })
+            .cloned()
+            .collect();
+
+        result.sort_by_key(|entry| Reverse(entry.updated()));
+
+        if let Some(max_results) = query.max_results {
+            result.truncate(max_results);
+        }
+
+        result
+    }
+}
+
+impl Handler for StreamsHandler {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        if req.url.path().join("/") != STREAMS_PATH.trim_start_matches('/') {
+            return Ok(Response::with(status::NotFound));
+        }
+
+        let query = match parse_query(req) {
+            Some(query) => query,
+            None => return Ok(Response::with(status::BadRequest)),
+        };
+
+        self.queries.lock().unwrap().push(query.clone());
+
+        let entries = self.entries(&query);
+        let updated = entries
+            .iter()
+            .map(|e| e.updated())
+            .max()
+            .unwrap_or_else(|| Utc::now().into());
+        let url = format!("{}", req.url);
+        let feed = Feed::new(&url, "Activity Streams", UTC_OFFSET, updated, &entries);
+        let mut body = Vec::new();
+
+        if entity::write_feed(&feed, &[("self", &url)], &mut body).is_err() {
+            return Ok(Response::with(status::InternalServerError));
+        }
+
+        if self.truncated.load(Ordering::SeqCst) {
+            let length = body.len() / 2;
+
+            body.truncate(length);
+        }
+
+        let mime: Mime = "application/atom+xml".parse().unwrap();
+
+        Ok(Response::with((status::Ok, mime, body)))
+    }
+}
+
+fn parse_query(req: &Request) -> Option<StreamsQuery> {
+    let url = req.url.as_ref();
+    let mut user = None;
+    let mut after = None;
+    let mut before = None;
+    let mut max_results = None;
+
+    for (name, value) in url.query_pairs() {
+        let words: Vec<&str> = value.split_whitespace().collect();
+
+        match (name.as_ref(), words.as_slice()) {
+            ("maxResults", _) => max_results = Some(value.parse().ok()?),
+            ("streams", ["user", "IS", name]) => user = Some(name.to_string()),
+            ("streams", ["update-date", "AFTER", start]) => after = Some(start.parse().ok()?),
+            ("streams", ["update-date", "BETWEEN", start, end]) => {
+                after = Some(start.parse().ok()?);
+                before = Some(end.parse().ok()?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(StreamsQuery {
+        user: user?,
+        after,
+        before,
+        max_results,
+    })
+}
@@ -0,0 +1,161 @@
+mod archive;
+mod backup;
+mod end_to_end;
+mod feed;
+mod import;
+mod issue;
+mod mock_streams;
+mod search;
+mod storage;
+
+use serde_yaml;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use config::Config;
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+const WAIT_STEP: Duration = Duration::from_millis(20);
+
+const JIRA: &[u8] = include_bytes!("../../fixtures/jira.xml");
+const BITBUCKET: &[u8] = include_bytes!("../../fixtures/bitbucket.xml");
+
+/// Temporary SQLite database file removed with its WAL files when dropped.
+struct TempDatabase {
+    path: PathBuf,
+}
+
+impl TempDatabase {
+    fn new(name: &str) -> TempDatabase {
+        let path = env::temp_dir().join(format!("team-activity-{}-{}.sqlite", name, process::id()));
+        let _ = fs::remove_file(&path);
+
+        TempDatabase { path }
+    }
+
+    fn path(&self) -> &str {
+        self.path
+            .to_str()
+            .expect("Temporary path is not valid UTF-8")
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+
+        for suffix in &["-wal", "-shm"] {
+            let _ = fs::remove_file(format!("{}{}", self.path(), suffix));
+        }
+    }
+}
+
+/// Creates configuration for members `jdoe` and `asmith` pulling activity from given URL.
+fn test_config(streams_url: &str, database: &TempDatabase, max_results: usize) -> Config {
+    test_config_with(
+        streams_url,
+        database,
+        &format!("max_results: {}", max_results),
+        "",
+    )
+}
+
+/// Creates test configuration with additional lines of `streams` section and additional
+/// top-level sections.
+fn test_config_with(
+    streams_url: &str,
+    database: &TempDatabase,
+    streams: &str,
+    sections: &str,
+) -> Config {
+    test_config_yaml(
+        streams_url,
+        &format!("path: \"{}\"", database.path()),
+        streams,
+        sections,
+    )
+}
+
+/// Creates test configuration with given lines of `database` section.
+fn test_config_yaml(streams_url: &str, database: &str, streams: &str, sections: &str) -> Config {
+    let yaml = format!(
+        r#"
+streams:
+  url: "{}"
+  username: "robot"
+  password: "secret"
+  {}
+
+database:
+  {}
+
+server:
+  address: "127.0.0.1"
+  port: 0
+
+members:
+  - jdoe
+  - asmith
+
+pull_interval: 3600
+
+activity:
+  ignore:
+    - application: com.atlassian.bitbucket
+      verbs: [ "http://activitystrea.ms/schema/1.0/push" ]
+
+  activities:
+    - application: com.atlassian.jira
+      key: task::create
+      group: ObjectIssue
+      verbs: [ "http://activitystrea.ms/schema/1.0/post" ]
+
+    - application: com.atlassian.jira
+      key: task::comment
+      group: TargetIssue
+      verbs: [ "http://activitystrea.ms/schema/1.0/post" ]
+
+    - application: com.atlassian.jira
+      key: task::resolve
+      group: ObjectIssue
+      verbs: [ "http://activitystrea.ms/schema/1.0/update", "http://streams.atlassian.com/syndication/verbs/jira/transition", "http://streams.atlassian.com/syndication/verbs/jira/resolve" ]
+
+    - application: com.atlassian.bitbucket
+      key: pull_request::open
+      group: ObjectPullRequest
+      verbs: [ "http://activitystrea.ms/schema/1.0/post" ]
+
+  messages:
+    task::create: [ "Created issue" ]
+    task::comment: [ "Discussed issue" ]
+    task::resolve: [ "Resolved issue" ]
+    pull_request::open: [ "Opened pull request" ]
+
+{}
+"#,
+        streams_url, streams, database, sections
+    );
+
+    serde_yaml::from_str(&yaml).expect("Invalid test configuration")
+}
+
+/// Polls condition until it holds, panics after timeout.
+fn wait_until<F>(description: &str, condition: F)
+where
+    F: Fn() -> bool,
+{
+    let deadline = Instant::now() + WAIT_TIMEOUT;
+
+    while !condition() {
+        if Instant::now() >= deadline {
+            panic!("Timed out waiting for {}", description);
+        }
+
+        thread::sleep(WAIT_STEP);
+    }
+}
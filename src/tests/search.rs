@@ -0,0 +1,68 @@
+use reqwest::Client;
+use serde_json;
+use serde_json::Value;
+
+use super::*;
+
+use command;
+use database::Database;
+use database::SearchQuery;
+use server;
+use worker::WorkerState;
+
+#[test]
+fn search_handler_returns_matches_of_members() {
+    let temp_database = TempDatabase::new("search-handler");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+    let paths = vec![
+        "fixtures/jira.xml".to_string(),
+        "fixtures/bitbucket.xml".to_string(),
+    ];
+
+    command::import(&config, &database, &paths).unwrap();
+
+    let matches = command::search(&database, &SearchQuery::new("report", 10)).unwrap();
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].author(), "asmith");
+
+    let state = WorkerState::new(config.members());
+    let server = server::start(&config, database, state).unwrap();
+    let url = format!("http://{}/api/v1/search", server.address());
+    let search = |query: &[(&str, &str)]| -> Value {
+        let mut response = Client::new().get(&url).query(query).send().unwrap();
+
+        serde_json::from_str(&response.text().unwrap()).unwrap()
+    };
+
+    let pull_request = search(&[("q", "session"), ("type", "pull-request")]);
+    let other_member = search(&[("q", "report"), ("member", "jdoe")]);
+    let unknown_member = search(&[("q", "report"), ("member", "nobody")]);
+    let missing_text = search(&[("member", "jdoe")]);
+
+    assert!(server.stop(Duration::from_secs(5)));
+
+    let stopped = Client::new().get(&url).send().unwrap();
+
+    assert_eq!(stopped.status().as_u16(), 503);
+
+    assert_eq!(pull_request["success"], Value::Bool(true));
+
+    let results = pull_request["results"].as_array().unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["member"], "jdoe");
+    assert_eq!(
+        results[0]["title"],
+        "John Doe opened pull request #42: PROJ-101 Refresh expired session"
+    );
+    assert!(results[0]["snippet"]
+        .as_str()
+        .unwrap()
+        .contains("**session**"));
+    assert!(other_member["results"].as_array().unwrap().is_empty());
+    assert_eq!(unknown_member["success"], Value::Bool(false));
+    assert_eq!(missing_text["success"], Value::Bool(false));
+    assert_eq!(missing_text["message"], "Missing query text");
+}
@@ -0,0 +1,390 @@
+use postgres::Client;
+use postgres::NoTls;
+use serde_yaml;
+use sqlite;
+
+use super::*;
+
+use command;
+use config::Config;
+use database::Database;
+use database::EntryData;
+use database::FeedQuery;
+use database::SearchQuery;
+use entity::Entry;
+
+/// Connection URL of disposable PostgreSQL database, its tables are dropped by tests.
+const POSTGRES_URL_VARIABLE: &str = "TEST_POSTGRES_URL";
+/// Jira comment on PROJ-101 serialized before issue keys and mentions were recorded.
+const LEGACY_ENTRY: &str = include_str!("../../fixtures/legacy_entry.yaml");
+
+fn entry(id: &str, published: i64, updated: i64) -> EntryData {
+    EntryData::new(
+        id,
+        published,
+        updated,
+        &format!("{} revision {}", id, updated),
+    )
+}
+
+/// Runs same scenario against any storage backend.
+fn check_storage(database: &Database) {
+    let stats = database
+        .save_entries("jdoe", &[entry("a", 100, 100), entry("b", 200, 200)])
+        .unwrap();
+
+    assert_eq!(stats.inserted(), 2);
+    assert_eq!(stats.updated(), 0);
+
+    let stats = database
+        .save_entries("jdoe", &[entry("a", 100, 150), entry("b", 200, 100)])
+        .unwrap();
+
+    assert_eq!(stats.inserted(), 0);
+    assert_eq!(stats.updated(), 1);
+
+    database
+        .save_entries("asmith", &[entry("c", 300, 300)])
+        .unwrap();
+
+    assert_eq!(database.last_published("jdoe").unwrap(), Some(200));
+    assert_eq!(database.last_published("nobody").unwrap(), None);
+    assert_eq!(
+        database.published_between("jdoe", 50, 150).unwrap(),
+        vec!["a revision 150".to_string()]
+    );
+    assert_eq!(
+        database
+            .feed_entries(&FeedQuery::new(100, 300, 0, 10))
+            .unwrap(),
+        vec![
+            ("asmith".to_string(), "c revision 300".to_string()),
+            ("jdoe".to_string(), "b revision 200".to_string()),
+            ("jdoe".to_string(), "a revision 150".to_string()),
+        ]
+    );
+    assert_eq!(
+        database
+            .feed_entries(&FeedQuery::new(100, 300, 1, 1).with_members(&["jdoe".to_string()]))
+            .unwrap(),
+        vec![("jdoe".to_string(), "a revision 150".to_string())]
+    );
+    assert_eq!(database.entry_count().unwrap(), 3);
+    assert_eq!(database.revision_count().unwrap(), 1);
+
+    let entries = database.entries_after("a", 1).unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].0, "jdoe");
+    assert_eq!(entries[0].1.id(), "b");
+
+    let revisions = database.revisions_after("", 0, 10).unwrap();
+
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].id(), "a");
+    assert_eq!(revisions[0].updated(), 100);
+    assert_eq!(revisions[0].data(), "a revision 100");
+
+    // Newest entry of every member is kept.
+    assert_eq!(database.delete_published_before(250).unwrap(), 1);
+    assert_eq!(database.entry_count().unwrap(), 2);
+    assert_eq!(database.revision_count().unwrap(), 0);
+    assert_eq!(database.last_published("jdoe").unwrap(), Some(200));
+}
+
+/// Returns identifiers of entries found by query.
+fn search(database: &Database, query: &SearchQuery) -> Vec<String> {
+    database
+        .search(query)
+        .unwrap()
+        .iter()
+        .map(|result| {
+            let entry: Entry = serde_yaml::from_str(result.data()).unwrap();
+
+            entry.id().to_string()
+        })
+        .collect()
+}
+
+/// Returns titles of entries referring to issue.
+fn issue_titles(database: &Database, issue_key: &str) -> Vec<String> {
+    database
+        .issue_entries(issue_key)
+        .unwrap()
+        .iter()
+        .map(|(_, data)| {
+            let entry: Entry = serde_yaml::from_str(data).unwrap();
+
+            entry.title_text()
+        })
+        .collect()
+}
+
+/// Runs search and issue index scenario against any storage backend, database must be empty.
+fn check_search(config: &Config, database: &Database) {
+    let paths = vec![
+        "fixtures/jira.xml".to_string(),
+        "fixtures/bitbucket.xml".to_string(),
+    ];
+
+    command::import(config, database, &paths).unwrap();
+
+    let results = database
+        .search(&SearchQuery::new("expired sessions", 10))
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|result| result.author() == "jdoe"));
+    assert!(results[0].snippet().contains("**expired**"));
+
+    let issue = SearchQuery::new("session", 10).with_object_type(Some(&"issue".to_string()));
+
+    assert_eq!(search(database, &issue).len(), 2);
+
+    let pull_request =
+        SearchQuery::new("session", 10).with_object_type(Some(&"pull-request".to_string()));
+
+    assert_eq!(
+        search(database, &pull_request),
+        vec!["urn:uuid:0a1b2c3d-4e5f-3a6b-7c8d-9e0f1a2b3c41".to_string()]
+    );
+
+    let bitbucket = SearchQuery::new("session", 10)
+        .with_application(Some(&"com.atlassian.bitbucket".to_string()));
+
+    assert_eq!(
+        search(database, &bitbucket),
+        search(database, &pull_request)
+    );
+
+    let member = SearchQuery::new("report", 10).with_members(&["asmith".to_string()]);
+
+    assert_eq!(search(database, &member).len(), 1);
+
+    let other_member = SearchQuery::new("report", 10).with_members(&["jdoe".to_string()]);
+
+    assert!(search(database, &other_member).is_empty());
+
+    let bitbucket_feed = FeedQuery::new(0, i64::MAX, 0, 10)
+        .with_application(Some(&"com.atlassian.bitbucket".to_string()));
+
+    assert_eq!(database.feed_entries(&bitbucket_feed).unwrap().len(), 2);
+
+    // Comment mentions CORE-7, punctuation is not query syntax.
+    assert_eq!(search(database, &SearchQuery::new("CORE-7", 10)).len(), 1);
+
+    // 2018-03-14T00:00:00Z
+    let interval = SearchQuery::new("session", 10).with_interval(Some(1_520_985_600), None);
+
+    assert_eq!(search(database, &interval).len(), 3);
+    assert!(search(database, &SearchQuery::new("", 10)).is_empty());
+
+    // Pull request only mentions PROJ-101 in its title.
+    assert_eq!(
+        issue_titles(database, "PROJ-101"),
+        vec![
+            "John Doe created PROJ-101 - Login fails with expired session".to_string(),
+            "John Doe commented on PROJ-101 - Login fails with expired session".to_string(),
+        ]
+    );
+    assert_eq!(issue_titles(database, "PROJ-98").len(), 1);
+    assert!(issue_titles(database, "PROJ-1").is_empty());
+
+    database.delete_published_before(i64::MAX).unwrap();
+
+    // Only newest entry of every member is kept in indexes.
+    assert!(search(database, &pull_request).is_empty());
+    assert_eq!(
+        search(database, &SearchQuery::new("session", 10)),
+        vec!["urn:uuid:3b8e7f62-1d4c-3a9e-b0c2-5e6f7a8b9c01".to_string()]
+    );
+    assert_eq!(
+        issue_titles(database, "PROJ-101"),
+        vec!["John Doe commented on PROJ-101 - Login fails with expired session".to_string()]
+    );
+}
+
+#[test]
+fn sqlite_storage() {
+    let temp_database = TempDatabase::new("storage");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+
+    check_storage(&Database::new(&config).unwrap());
+}
+
+#[test]
+fn sqlite_search() {
+    let temp_database = TempDatabase::new("search");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+
+    check_search(&config, &Database::new(&config).unwrap());
+}
+
+#[test]
+fn sqlite_update_indexes_stored_entries() {
+    let temp_database = TempDatabase::new("search-update");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let paths = vec!["fixtures/jira.xml".to_string()];
+
+    command::import(&config, &Database::new(&config).unwrap(), &paths).unwrap();
+
+    // Revert schema to version before search index and store comment in format of that version.
+    let connection = sqlite::open(temp_database.path()).unwrap();
+
+    connection
+        .execute(
+            "DROP TABLE entry_search ; DROP TABLE entry_document ; DROP TABLE entry_issue ; PRAGMA user_version = 3",
+        )
+        .unwrap();
+
+    let mut statement = connection
+        .prepare(
+            "UPDATE entry SET data = ? WHERE id = 'urn:uuid:3b8e7f62-1d4c-3a9e-b0c2-5e6f7a8b9c01'",
+        )
+        .unwrap();
+
+    statement.bind(1, LEGACY_ENTRY).unwrap();
+    statement.next().unwrap();
+    drop(statement);
+    drop(connection);
+
+    let database = Database::new(&config).unwrap();
+
+    assert_eq!(search(&database, &SearchQuery::new("session", 10)).len(), 2);
+    assert_eq!(
+        issue_titles(&database, "PROJ-101"),
+        vec![
+            "John Doe created PROJ-101 - Login fails with expired session".to_string(),
+            "John Doe commented on PROJ-101 - Login fails with expired session".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn sqlite_failed_update_is_applied_again() {
+    let temp_database = TempDatabase::new("search-update-failed");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let paths = vec!["fixtures/jira.xml".to_string()];
+
+    command::import(&config, &Database::new(&config).unwrap(), &paths).unwrap();
+
+    // Revert schema to version before search index and store entry which can not be read.
+    let connection = sqlite::open(temp_database.path()).unwrap();
+    let version = |connection: &sqlite::Connection| {
+        let mut cursor = connection.prepare("PRAGMA user_version").unwrap().cursor();
+
+        cursor.next().unwrap().unwrap()[0].as_integer()
+    };
+
+    connection
+        .execute(
+            "DROP TABLE entry_search ; DROP TABLE entry_document ; DROP TABLE entry_issue ; PRAGMA user_version = 3 ; UPDATE entry SET published = 'invalid' WHERE id = ( SELECT MIN(id) FROM entry )",
+        )
+        .unwrap();
+
+    assert!(Database::new(&config).is_err());
+    assert_eq!(version(&connection), Some(3));
+
+    connection
+        .execute(
+            "UPDATE entry SET published = 0 WHERE published = 'invalid'",
+        )
+        .unwrap();
+
+    let database = Database::new(&config).unwrap();
+
+    assert_eq!(search(&database, &SearchQuery::new("session", 10)).len(), 2);
+}
+
+#[test]
+fn sqlite_reads_are_not_blocked_by_writes() {
+    let temp_database = TempDatabase::new("wal");
+    let config = test_config("http://127.0.0.1:1/streams", &temp_database, 25);
+    let database = Database::new(&config).unwrap();
+
+    database
+        .save_entries("jdoe", &[entry("a", 100, 100)])
+        .unwrap();
+
+    let connection = sqlite::open(temp_database.path()).unwrap();
+    let mut cursor = connection.prepare("PRAGMA journal_mode").unwrap().cursor();
+
+    assert_eq!(cursor.next().unwrap().unwrap()[0].as_string(), Some("wal"));
+
+    connection
+        .execute(
+            "BEGIN EXCLUSIVE ; INSERT INTO entry ( id, author, published, updated, data ) VALUES ( 'b', 'jdoe', 200, 200, 'b' )",
+        )
+        .unwrap();
+
+    // Writer waits for lock held by other connection.
+    let writer = {
+        let database = database.clone();
+
+        thread::spawn(move || database.save_entries("asmith", &[entry("c", 300, 300)]))
+    };
+
+    thread::sleep(Duration::from_millis(100));
+
+    let readers: Vec<_> = (0..8)
+        .map(|_| {
+            let database = database.clone();
+
+            thread::spawn(move || database.last_published("jdoe").unwrap())
+        })
+        .collect();
+
+    for reader in readers {
+        assert_eq!(reader.join().unwrap(), Some(100));
+    }
+
+    assert!(!writer.is_finished());
+
+    connection.execute("COMMIT").unwrap();
+    writer.join().unwrap().unwrap();
+
+    assert_eq!(database.last_published("jdoe").unwrap(), Some(200));
+    assert_eq!(database.last_published("asmith").unwrap(), Some(300));
+}
+
+/// Needs disposable PostgreSQL database, run with
+/// `TEST_POSTGRES_URL="postgres://postgres@localhost/activity_test" cargo test -- --ignored`.
+#[test]
+#[ignore]
+fn postgres_storage() {
+    let url = env::var(POSTGRES_URL_VARIABLE)
+        .unwrap_or_else(|_| panic!("{} is not set", POSTGRES_URL_VARIABLE));
+
+    let config = test_config_yaml(
+        "http://127.0.0.1:1/streams",
+        &format!("backend: Postgres\n  url: \"{}\"", url),
+        "",
+        "",
+    );
+    let mut client = Client::connect(&url, NoTls).unwrap();
+    let drop_tables =
+        "DROP TABLE IF EXISTS entry, entry_revision, entry_document, entry_issue, schema_version";
+
+    client.batch_execute(drop_tables).unwrap();
+
+    check_storage(&Database::new(&config).unwrap());
+
+    // Schema is not created again for existing database.
+    let database = Database::new(&config).unwrap();
+
+    assert_eq!(database.entry_count().unwrap(), 2);
+
+    // Query fails on connection closed by server, connection is replaced when used again.
+    client
+        .batch_execute(
+            "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = current_database() AND pid <> pg_backend_pid()",
+        )
+        .unwrap();
+
+    assert!(database.entry_count().is_err());
+    assert_eq!(database.entry_count().unwrap(), 2);
+
+    client.batch_execute(drop_tables).unwrap();
+
+    check_search(&config, &Database::new(&config).unwrap());
+}
@@ -9,9 +9,9 @@ pub type WorkerResult<T> = Result<T, WorkerError>;
 pub enum WorkerError {
     DatabaseError { message: String },
     EntityReadError { message: String },
-    DateParseError { message: String },
     SerializationError { message: String },
     StreamError { message: String },
+    ScheduleError { expression: String, message: String },
 }
 
 impl WorkerError {
@@ -37,17 +37,6 @@ impl WorkerError {
         }
     }
 
-    pub fn date_parse_error<E>(error: E) -> WorkerError
-    where
-        E: Error,
-    {
-        warn!("Date parse error: {}", error);
-
-        WorkerError::DateParseError {
-            message: format!("{}", error),
-        }
-    }
-
     pub fn serialization_error<E>(error: E) -> WorkerError
     where
         E: Error,
@@ -69,6 +58,15 @@ impl WorkerError {
             message: format!("{}", error),
         }
     }
+
+    pub fn schedule_error(expression: &str, message: &str) -> WorkerError {
+        warn!("Schedule error: {} in `{}`", message, expression);
+
+        WorkerError::ScheduleError {
+            expression: expression.into(),
+            message: message.into(),
+        }
+    }
 }
 
 impl Display for WorkerError {
@@ -80,13 +78,14 @@ impl Display for WorkerError {
             WorkerError::EntityReadError { ref message } => {
                 write!(f, "Entity read error: `{}`", message)
             }
-            WorkerError::DateParseError { ref message } => {
-                write!(f, "Date parse error: `{}`", message)
-            }
             WorkerError::SerializationError { ref message } => {
                 write!(f, "Serialization error: `{}`", message)
             }
             WorkerError::StreamError { ref message } => write!(f, "Stream error: `{}`", message),
+            WorkerError::ScheduleError {
+                ref expression,
+                ref message,
+            } => write!(f, "Schedule error: {} in `{}`", message, expression),
         }
     }
 }
@@ -1,42 +1,98 @@
-use serde_yaml;
-use std::thread;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::thread::Builder;
 use std::thread::JoinHandle;
 use std::time::Duration as StdDuration;
 use std::time::Instant;
-use time::strptime;
-use time::Duration;
-use time::Timespec;
+use time;
 
 use config::Config;
 use database::Database;
-use entity;
+use entity::FeedFormat;
 use stream::ActivityStreamsClient;
 
 mod error;
+mod schedule;
+mod state;
+mod updater;
+
+pub use self::state::WorkerCommand;
+pub use self::state::WorkerRunState;
+pub use self::state::WorkerState;
+pub use self::state::WorkerStatus;
+pub use self::updater::entry_data;
 
 use self::error::WorkerError;
 use self::error::WorkerResult;
+use self::schedule::Schedule;
+use self::updater::MemberUpdater;
+use self::updater::SourcePositions;
+
+const NO_SCHEDULE_RECHECK: StdDuration = StdDuration::from_secs(24 * 60 * 60);
+const STREAMS_SOURCE: &str = "streams";
 
 #[derive(Debug)]
 pub struct Worker {
-    config: Config,
-    pull_interval: u64,
+    revision_lookback: u64,
+    concurrency: usize,
+    sources: Vec<Source>,
     members: Vec<String>,
     database: Database,
+    state: WorkerState,
+}
+
+/// Streams server updated on own schedule.
+#[derive(Debug)]
+struct Source {
+    name: String,
+    client: ActivityStreamsClient,
+    format: FeedFormat,
+    schedule: Schedule,
+    positions: Option<SourcePositions>,
 }
 
 impl Worker {
-    pub fn new(config: &Config, database: Database) -> Worker {
+    pub fn new(config: &Config, database: Database, state: WorkerState) -> WorkerResult<Worker> {
         let pull_interval = config.pull_interval();
+        let streams = config.streams();
+        let client = ActivityStreamsClient::new(config);
+        let mut sources = vec![Source {
+            name: STREAMS_SOURCE.into(),
+            client: client.clone(),
+            format: streams.format(),
+            schedule: Schedule::new(pull_interval, streams.schedule())?,
+            positions: None,
+        }];
+
+        for source in streams.sources() {
+            sources.push(Source {
+                name: source.name().into(),
+                client: client.clone().with_source(source),
+                format: source.format(),
+                schedule: Schedule::new(pull_interval, source.schedule())?,
+                positions: None,
+            });
+        }
+
+        // Newest stored entry of member can come from other source, so every source keeps own
+        // position.
+        if sources.len() > 1 {
+            for source in &mut sources {
+                source.positions = Some(SourcePositions::default());
+            }
+        }
+
+        let concurrency = streams.concurrency().max(1);
         let members = config.members().into();
 
-        Worker {
-            config: config.clone(),
-            pull_interval,
+        Ok(Worker {
+            revision_lookback: config.revision_lookback(),
+            concurrency,
+            sources,
             members,
             database,
-        }
+            state,
+        })
     }
 
     pub fn start(self) -> JoinHandle<()> {
@@ -49,92 +105,180 @@ impl Worker {
     }
 
     fn run(self) {
-        let client = ActivityStreamsClient::new(&self.config);
+        let _alive = AliveGuard::new(&self.state);
+        // Time of next round of every source, `None` if schedule has no round during next year.
+        let mut next_runs = vec![Some(0); self.sources.len()];
 
         info!("Worker started");
 
-        let mut start_time = Instant::now();
-        let mut end_time;
-        let interval = StdDuration::from_secs(self.pull_interval);
+        while !self.state.is_stopping() {
+            let round_start = time::get_time().sec;
+            let due: Vec<usize> = (0..self.sources.len())
+                .filter(|&index| next_runs[index].map_or(false, |time| time <= round_start))
+                .collect();
+            let next_run = if due.is_empty() {
+                next_runs.iter().filter_map(|&next_run| next_run).min()
+            } else {
+                let start_time = Instant::now();
+
+                self.state.round_started();
+
+                for index in due {
+                    let source = &self.sources[index];
+
+                    self.update_activity(source, &self.members);
+
+                    next_runs[index] = source.schedule.next_run(round_start, time::get_time().sec);
+
+                    if next_runs[index].is_none() {
+                        warn!(
+                            "No scheduled rounds of {} during next year, waiting for commands",
+                            source.name
+                        );
+                    }
+                }
+
+                let elapsed = start_time.elapsed();
+                let next_run = next_runs.iter().filter_map(|&next_run| next_run).min();
+
+                info!("Worker round time {}", elapsed.as_secs());
+
+                self.state.round_finished(elapsed.as_secs(), next_run);
+
+                next_run
+            };
+            let sleep_interval = match next_run {
+                Some(next_run) => {
+                    StdDuration::from_secs((next_run - time::get_time().sec).max(0) as u64)
+                }
+                None => NO_SCHEDULE_RECHECK,
+            };
+
+            if self.wait_next_round(Instant::now() + sleep_interval) {
+                next_runs = vec![Some(0); self.sources.len()];
+            }
+        }
+    }
+
+    /// Sleeps until deadline, handling commands received from HTTP handlers. Returns `true` if
+    /// full update was requested.
+    fn wait_next_round(&self, deadline: Instant) -> bool {
+        debug!(
+            "Worker sleeping for {}",
+            deadline.duration_since(Instant::now()).as_secs()
+        );
 
         loop {
-            end_time = start_time;
+            let now = Instant::now();
 
-            if let Err(err) = self.update_activity(&client) {
-                warn!("Failed to update last activity: {}", err);
+            if now >= deadline {
+                return false;
             }
 
-            start_time = Instant::now();
+            match self.state.wait_command(deadline - now) {
+                Some(WorkerCommand::UpdateAll) => {
+                    info!("Full update requested");
 
-            let elapsed = start_time.duration_since(end_time);
+                    return true;
+                }
+                Some(WorkerCommand::Stop) => {
+                    info!("Stop requested");
 
-            info!("Worker round time {}", elapsed.as_secs());
+                    return false;
+                }
+                Some(WorkerCommand::UpdateMember(member)) => {
+                    info!("Update requested for {}", member);
 
-            if elapsed < interval {
-                let sleep_interval = interval - elapsed;
+                    self.state.refresh_started();
 
-                debug!("Worker sleeping for {}", sleep_interval.as_secs());
+                    for source in &self.sources {
+                        self.update_activity(source, &[member.clone()]);
+                    }
 
-                thread::sleep(sleep_interval);
+                    self.state.refresh_finished();
+                }
+                None => return false,
             }
         }
     }
 
-    fn update_activity(&self, client: &ActivityStreamsClient) -> WorkerResult<()> {
-        let interval = Duration::seconds(self.pull_interval as i64);
+    /// Updates activity of given members from source. Members processed concurrently if
+    /// concurrency greater than one.
+    fn update_activity(&self, source: &Source, members: &[String]) {
+        let mut updater = MemberUpdater::new(
+            &source.client,
+            &self.database,
+            &self.state,
+            self.revision_lookback,
+            source.format,
+        );
 
-        for member in &self.members {
-            info!("Processing {}", member);
+        if let Some(ref positions) = source.positions {
+            updater = updater.with_positions(positions);
+        }
 
-            let last_published = self
-                .database
-                .last_published(member)
-                .map_err(WorkerError::database_error)?;
-            let result = if let Some(published) = last_published {
-                let published_time = Timespec::new(published, 0);
-                let start_time = published_time - interval;
+        let n_threads = self.concurrency.min(members.len());
 
-                debug!("Query activity for {} since {}", member, start_time.sec);
+        if n_threads <= 1 {
+            for member in members {
+                if self.state.is_stopping() {
+                    info!("Worker stopping, skipping remaining members");
 
-                client
-                    .query_after(member, start_time.sec * 1000)
-                    .map_err(WorkerError::stream_error)?
-            } else {
-                debug!("Query all activity for {}", member);
+                    break;
+                }
 
-                client.query(member).map_err(WorkerError::stream_error)?
-            };
-            let bytes = result.as_bytes();
-            let feed = entity::read(bytes).map_err(WorkerError::entity_read_error)?;
-            let entries = feed.entries();
-
-            for entry in entries {
-                let id = entry.id();
-
-                debug!("Processing entry {}", id);
-
-                if !self
-                    .database
-                    .has_entry(id)
-                    .map_err(WorkerError::database_error)?
-                {
-                    let published = strptime(entry.published(), "%Y-%m-%dT%H:%M:%S")
-                        .map_err(WorkerError::date_parse_error)?
-                        .to_timespec();
-                    let data =
-                        serde_yaml::to_string(entry).map_err(WorkerError::serialization_error)?;
-
-                    self.database
-                        .save_entry(id, member, published.sec, &data)
-                        .map_err(WorkerError::database_error)?;
-
-                    debug!("Entry saved: {}", id);
-                } else {
-                    debug!("Entry already processed: {}", id);
+                updater.update(member);
+            }
+        } else {
+            debug!(
+                "Updating {} members in {} threads",
+                members.len(),
+                n_threads
+            );
+
+            let queue = Arc::new(Mutex::new(members.iter().cloned().collect()));
+            let handles: Vec<_> = (0..n_threads)
+                .map(|index| {
+                    let updater = updater.clone();
+                    let queue = queue.clone();
+
+                    Builder::new()
+                        .name(format!("Stream worker {}", index))
+                        .spawn(move || updater.update_queue(&queue))
+                })
+                .collect();
+
+            for handle in handles {
+                match handle {
+                    Ok(handle) => {
+                        if handle.join().is_err() {
+                            warn!("Member update thread panicked");
+                        }
+                    }
+                    Err(err) => warn!("Failed to start member update thread: {}", err),
                 }
             }
         }
+    }
+}
+
+/// Marks worker as not alive when worker thread exits, including exit by panic.
+struct AliveGuard<'a> {
+    state: &'a WorkerState,
+}
+
+impl<'a> AliveGuard<'a> {
+    fn new(state: &'a WorkerState) -> AliveGuard<'a> {
+        state.set_alive(true);
+
+        AliveGuard { state }
+    }
+}
+
+impl<'a> Drop for AliveGuard<'a> {
+    fn drop(&mut self) {
+        info!("Worker stopped");
 
-        Ok(())
+        self.state.set_alive(false);
     }
 }
@@ -0,0 +1,282 @@
+use time;
+use time::Timespec;
+
+use super::WorkerError;
+use super::WorkerResult;
+
+const SECONDS_PER_MINUTE: i64 = 60;
+const MAX_SEARCH_MINUTES: i64 = 366 * 24 * 60;
+
+/// Defines when worker should start next round.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Interval { seconds: u64 },
+    Cron { expressions: Vec<CronExpression> },
+}
+
+impl Schedule {
+    /// Creates schedule from cron expressions. If expression list is empty fixed pull interval
+    /// will be used.
+    pub fn new(pull_interval: u64, expressions: &[String]) -> WorkerResult<Schedule> {
+        if expressions.is_empty() {
+            Ok(Schedule::Interval {
+                seconds: pull_interval,
+            })
+        } else {
+            let expressions = expressions
+                .iter()
+                .map(|e| CronExpression::parse(e))
+                .collect::<WorkerResult<_>>()?;
+
+            Ok(Schedule::Cron { expressions })
+        }
+    }
+
+    /// Returns UNIX time of next round. Returns `None` if no round matches schedule during next
+    /// year.
+    pub fn next_run(&self, round_start: i64, now: i64) -> Option<i64> {
+        match self {
+            Schedule::Interval { seconds } => {
+                let next_run = round_start + *seconds as i64;
+
+                if next_run > now {
+                    Some(next_run)
+                } else {
+                    Some(now)
+                }
+            }
+            Schedule::Cron { ref expressions } => {
+                let first_minute = now / SECONDS_PER_MINUTE + 1;
+
+                for minute in first_minute..first_minute + MAX_SEARCH_MINUTES {
+                    let time = minute * SECONDS_PER_MINUTE;
+                    let tm = time::at(Timespec::new(time, 0));
+
+                    if expressions.iter().any(|e| e.matches(&tm)) {
+                        return Some(time);
+                    }
+                }
+
+                None
+            }
+        }
+    }
+}
+
+/// Standard five field cron expression: minute, hour, day of month, month and day of week.
+/// Every field supports `*`, single values, ranges `a-b`, steps `*/n` or `a-b/n` and comma
+/// separated lists. Expressions are evaluated in local time zone.
+#[derive(Debug, Clone)]
+pub struct CronExpression {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronExpression {
+    pub fn parse(expression: &str) -> WorkerResult<CronExpression> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+
+        if fields.len() != 5 {
+            return Err(WorkerError::schedule_error(
+                expression,
+                "expected five fields",
+            ));
+        }
+
+        let minutes = parse_field(expression, fields[0], 0, 59)?;
+        let hours = parse_field(expression, fields[1], 0, 23)?;
+        let days = parse_field(expression, fields[2], 1, 31)?;
+        let months = parse_field(expression, fields[3], 1, 12)?;
+        let mut weekdays = parse_field(expression, fields[4], 0, 7)?;
+
+        // Both 0 and 7 are Sunday.
+        if weekdays[7] {
+            weekdays[0] = true;
+        }
+
+        Ok(CronExpression {
+            minutes,
+            hours,
+            days,
+            months,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    fn matches(&self, tm: &time::Tm) -> bool {
+        let day_matches = self.days[tm.tm_mday as usize];
+        let weekday_matches = self.weekdays[tm.tm_wday as usize];
+        let date_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => day_matches || weekday_matches,
+            _ => day_matches && weekday_matches,
+        };
+
+        self.minutes[tm.tm_min as usize]
+            && self.hours[tm.tm_hour as usize]
+            && self.months[tm.tm_mon as usize + 1]
+            && date_matches
+    }
+}
+
+fn parse_field(expression: &str, field: &str, min: usize, max: usize) -> WorkerResult<Vec<bool>> {
+    let mut result = vec![false; max + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(index) => {
+                let step = parse_number(expression, &part[index + 1..])?;
+
+                if step == 0 {
+                    return Err(WorkerError::schedule_error(
+                        expression,
+                        "step must be positive",
+                    ));
+                }
+
+                (&part[..index], step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some(index) = range.find('-') {
+            let start = parse_number(expression, &range[..index])?;
+            let end = parse_number(expression, &range[index + 1..])?;
+
+            (start, end)
+        } else {
+            let start = parse_number(expression, range)?;
+
+            if step > 1 {
+                (start, max)
+            } else {
+                (start, start)
+            }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(WorkerError::schedule_error(
+                expression,
+                "value out of range",
+            ));
+        }
+
+        for value in (start..end + 1).step_by(step) {
+            result[value] = true;
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_number(expression: &str, text: &str) -> WorkerResult<usize> {
+    text.parse()
+        .map_err(|_| WorkerError::schedule_error(expression, "invalid number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use time;
+
+    use super::CronExpression;
+
+    /// Returns values enabled in parsed field.
+    fn values(field: &[bool]) -> Vec<usize> {
+        (0..field.len()).filter(|&value| field[value]).collect()
+    }
+
+    /// Returns time of given day of month and week in January.
+    fn tm(day: i32, weekday: i32, hour: i32, minute: i32) -> time::Tm {
+        time::Tm {
+            tm_min: minute,
+            tm_hour: hour,
+            tm_mday: day,
+            tm_mon: 0,
+            tm_wday: weekday,
+            ..time::empty_tm()
+        }
+    }
+
+    #[test]
+    fn parse_ranges() {
+        let expression = CronExpression::parse("0 9-17 * * 1-5").unwrap();
+
+        assert_eq!(values(&expression.minutes), vec![0]);
+        assert_eq!(values(&expression.hours), (9..18).collect::<Vec<_>>());
+        assert_eq!(values(&expression.days), (1..32).collect::<Vec<_>>());
+        assert_eq!(values(&expression.weekdays), vec![1, 2, 3, 4, 5]);
+        assert!(expression.any_day);
+        assert!(!expression.any_weekday);
+    }
+
+    #[test]
+    fn parse_steps() {
+        let expression = CronExpression::parse("*/15 5-20/5 10/10 */6 *").unwrap();
+
+        assert_eq!(values(&expression.minutes), vec![0, 15, 30, 45]);
+        assert_eq!(values(&expression.hours), vec![5, 10, 15, 20]);
+        assert_eq!(values(&expression.days), vec![10, 20, 30]);
+        assert_eq!(values(&expression.months), vec![1, 7]);
+    }
+
+    #[test]
+    fn parse_lists() {
+        let expression = CronExpression::parse("0,30 8,12-13,18 1,15 * 6,7").unwrap();
+
+        assert_eq!(values(&expression.minutes), vec![0, 30]);
+        assert_eq!(values(&expression.hours), vec![8, 12, 13, 18]);
+        assert_eq!(values(&expression.days), vec![1, 15]);
+        // Both 0 and 7 are Sunday.
+        assert_eq!(values(&expression.weekdays), vec![0, 6, 7]);
+    }
+
+    #[test]
+    fn parse_invalid_fields() {
+        let invalid = [
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "1- * * * *",
+            "1,,2 * * * *",
+        ];
+
+        for expression in &invalid {
+            assert!(
+                CronExpression::parse(expression).is_err(),
+                "{} was parsed",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn match_day_of_month_or_week() {
+        let weekdays = CronExpression::parse("*/15 9-17 * * 1-5").unwrap();
+
+        assert!(weekdays.matches(&tm(1, 1, 9, 45)));
+        assert!(!weekdays.matches(&tm(1, 1, 9, 50)));
+        assert!(!weekdays.matches(&tm(1, 1, 18, 0)));
+        assert!(!weekdays.matches(&tm(6, 6, 9, 0)));
+
+        // Restricted day of month and week match either of them.
+        let either = CronExpression::parse("0 0 1 * 1").unwrap();
+
+        assert!(either.matches(&tm(1, 4, 0, 0)));
+        assert!(either.matches(&tm(5, 1, 0, 0)));
+        assert!(!either.matches(&tm(6, 2, 0, 0)));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration as StdDuration;
+use std::time::Instant;
+use time;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkerCommand {
+    UpdateAll,
+    UpdateMember(String),
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum WorkerRunState {
+    Idle,
+    Running,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MemberStatus {
+    last_success: Option<i64>,
+    last_error: Option<String>,
+    last_error_time: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    alive: bool,
+    state: WorkerRunState,
+    last_round_start: Option<i64>,
+    last_round_duration: Option<u64>,
+    next_run: Option<i64>,
+    members: HashMap<String, MemberStatus>,
+}
+
+impl WorkerStatus {
+    pub fn state(&self) -> WorkerRunState {
+        self.state
+    }
+}
+
+#[derive(Debug)]
+struct WorkerStateInner {
+    status: WorkerStatus,
+    commands: VecDeque<WorkerCommand>,
+    stopping: bool,
+}
+
+/// Shared state of background worker. Used by worker thread to report progress and by HTTP
+/// handlers to read status and send commands.
+#[derive(Debug, Clone)]
+pub struct WorkerState {
+    inner: Arc<Mutex<WorkerStateInner>>,
+    condvar: Arc<Condvar>,
+}
+
+impl WorkerState {
+    pub fn new(members: &[String]) -> WorkerState {
+        let members = members
+            .iter()
+            .map(|member| (member.clone(), MemberStatus::default()))
+            .collect();
+        let status = WorkerStatus {
+            alive: false,
+            state: WorkerRunState::Idle,
+            last_round_start: None,
+            last_round_duration: None,
+            next_run: None,
+            members,
+        };
+
+        WorkerState {
+            inner: Arc::new(Mutex::new(WorkerStateInner {
+                status,
+                commands: VecDeque::new(),
+                stopping: false,
+            })),
+            condvar: Arc::new(Condvar::new()),
+        }
+    }
+
+    pub fn status(&self) -> WorkerStatus {
+        self.lock().status.clone()
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.lock().status.alive
+    }
+
+    pub fn set_alive(&self, alive: bool) {
+        let mut inner = self.lock();
+
+        inner.status.alive = alive;
+        inner.status.state = WorkerRunState::Idle;
+    }
+
+    pub fn round_started(&self) {
+        let mut inner = self.lock();
+
+        inner.status.state = WorkerRunState::Running;
+        inner.status.last_round_start = Some(time::get_time().sec);
+    }
+
+    pub fn round_finished(&self, duration: u64, next_run: Option<i64>) {
+        let mut inner = self.lock();
+
+        inner.status.state = WorkerRunState::Idle;
+        inner.status.last_round_duration = Some(duration);
+        inner.status.next_run = next_run;
+    }
+
+    pub fn refresh_started(&self) {
+        self.lock().status.state = WorkerRunState::Running;
+    }
+
+    pub fn refresh_finished(&self) {
+        self.lock().status.state = WorkerRunState::Idle;
+    }
+
+    pub fn member_success(&self, member: &str) {
+        let mut inner = self.lock();
+        let status = inner
+            .status
+            .members
+            .entry(member.into())
+            .or_insert_with(MemberStatus::default);
+
+        status.last_success = Some(time::get_time().sec);
+    }
+
+    pub fn member_failed(&self, member: &str, message: &str) {
+        let mut inner = self.lock();
+        let status = inner
+            .status
+            .members
+            .entry(member.into())
+            .or_insert_with(MemberStatus::default);
+
+        status.last_error = Some(message.into());
+        status.last_error_time = Some(time::get_time().sec);
+    }
+
+    /// Queues command for worker and wakes it up if it is waiting for next round.
+    pub fn send_command(&self, command: WorkerCommand) {
+        let mut inner = self.lock();
+
+        if !inner.stopping && !inner.commands.contains(&command) {
+            inner.commands.push_back(command);
+        }
+
+        self.condvar.notify_all();
+    }
+
+    /// Asks worker to stop after current member. All queued commands will be discarded.
+    pub fn stop(&self) {
+        let mut inner = self.lock();
+
+        inner.stopping = true;
+        inner.commands.clear();
+        inner.commands.push_back(WorkerCommand::Stop);
+
+        self.condvar.notify_all();
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.lock().stopping
+    }
+
+    /// Waits for next command until timeout expires. Returns `None` if no commands received.
+    pub fn wait_command(&self, timeout: StdDuration) -> Option<WorkerCommand> {
+        let deadline = Instant::now() + timeout;
+        let mut inner = self.lock();
+
+        loop {
+            if let Some(command) = inner.commands.pop_front() {
+                return Some(command);
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return None;
+            }
+
+            inner = match self.condvar.wait_timeout(inner, deadline - now) {
+                Ok((inner, _)) => inner,
+                Err(err) => err.into_inner().0,
+            };
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<WorkerStateInner> {
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(err) => {
+                warn!("Worker state mutex poisoned");
+
+                err.into_inner()
+            }
+        }
+    }
+}
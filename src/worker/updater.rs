@@ -0,0 +1,241 @@
+use serde_yaml;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use time::Duration;
+use time::Timespec;
+
+use database::Database;
+use database::DatabaseError;
+use database::EntryData;
+use entity;
+use entity::Entry;
+use entity::EntryError;
+use entity::FeedFormat;
+use stream::ActivityStreamsClient;
+
+use super::WorkerError;
+use super::WorkerResult;
+use super::WorkerState;
+
+/// Loads activity of single member and stores new entries to database. Can be cloned to update
+/// several members concurrently.
+#[derive(Debug, Clone)]
+pub struct MemberUpdater {
+    client: ActivityStreamsClient,
+    database: Database,
+    state: WorkerState,
+    revision_lookback: u64,
+    format: FeedFormat,
+    positions: Option<SourcePositions>,
+}
+
+/// Publication time of newest entry read from source for every member. Kept in memory, so first
+/// update after start loads newest activity of member from source again.
+#[derive(Debug, Clone, Default)]
+pub struct SourcePositions {
+    positions: Arc<Mutex<HashMap<String, i64>>>,
+}
+
+impl SourcePositions {
+    fn get(&self, member: &str) -> Option<i64> {
+        match self.positions.lock() {
+            Ok(positions) => positions.get(member).cloned(),
+            Err(_) => None,
+        }
+    }
+
+    /// Moves position of member forward to given time.
+    fn advance(&self, member: &str, published: i64) {
+        if let Ok(mut positions) = self.positions.lock() {
+            let position = positions.entry(member.into()).or_insert(published);
+
+            *position = (*position).max(published);
+        }
+    }
+}
+
+impl MemberUpdater {
+    pub fn new(
+        client: &ActivityStreamsClient,
+        database: &Database,
+        state: &WorkerState,
+        revision_lookback: u64,
+        format: FeedFormat,
+    ) -> MemberUpdater {
+        MemberUpdater {
+            client: client.clone(),
+            database: database.clone(),
+            state: state.clone(),
+            revision_lookback,
+            format,
+            positions: None,
+        }
+    }
+
+    /// Updates members from position of source instead of newest stored entry, which can come
+    /// from other source.
+    pub fn with_positions(mut self, positions: &SourcePositions) -> MemberUpdater {
+        self.positions = Some(positions.clone());
+        self
+    }
+
+    /// Updates members from shared queue until queue is empty or worker is stopping.
+    pub fn update_queue(&self, queue: &Mutex<VecDeque<String>>) {
+        loop {
+            if self.state.is_stopping() {
+                info!("Worker stopping, skipping remaining members");
+
+                break;
+            }
+
+            let member = match queue.lock() {
+                Ok(mut queue) => queue.pop_front(),
+                Err(err) => err.into_inner().pop_front(),
+            };
+
+            match member {
+                Some(member) => self.update(&member),
+                None => break,
+            }
+        }
+    }
+
+    /// Updates member activity and reports result to worker state.
+    pub fn update(&self, member: &str) {
+        info!("Processing {}", member);
+
+        match self.update_member(member) {
+            Ok(()) => self.state.member_success(member),
+            Err(err) => {
+                warn!("Failed to update activity for {}: {}", member, err);
+
+                self.state.member_failed(member, &format!("{}", err));
+            }
+        }
+    }
+
+    fn update_member(&self, member: &str) -> WorkerResult<()> {
+        let revision_lookback = Duration::seconds(self.revision_lookback as i64);
+        let last_published = match self.positions {
+            Some(ref positions) => positions.get(member),
+            None => self
+                .database
+                .last_published(member)
+                .map_err(WorkerError::database_error)?,
+        };
+        let response = if let Some(published) = last_published {
+            let published_time = Timespec::new(published, 0);
+            // Entries updated after start time are loaded again to store their new revisions.
+            let start_time = published_time - revision_lookback;
+
+            debug!("Query activity for {} since {}", member, start_time.sec);
+
+            self.client
+                .query_after(member, start_time.sec * 1000)
+                .map_err(WorkerError::stream_error)?
+        } else {
+            debug!("Query all activity for {}", member);
+
+            self.client
+                .query(member)
+                .map_err(WorkerError::stream_error)?
+        };
+        // Configured format takes precedence over format declared by response.
+        let format = match self.format {
+            FeedFormat::Auto => response
+                .content_type()
+                .and_then(|content_type| FeedFormat::from_media_type(&content_type))
+                .unwrap_or(FeedFormat::Auto),
+            format => format,
+        };
+        let entries =
+            entity::read_entries(format, response).map_err(WorkerError::entity_read_error)?;
+        let mut failure = None;
+        let mut newest = None;
+        let mut count = 0;
+        let mut skipped = 0;
+        let mut degraded = 0;
+
+        // Entries are stored as they are read, but committed only when whole response was read,
+        // otherwise newest entries would move last published time past older entries which were
+        // not read.
+        let result = {
+            let mut data = entries.filter_map(|entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err @ EntryError::XmlEventError { .. }) => {
+                        failure = Some(WorkerError::entity_read_error(err));
+
+                        return Some(Err(DatabaseError::interrupted()));
+                    }
+                    Err(err) => {
+                        warn!("Skipping entry of {}: {}", member, err);
+
+                        skipped += 1;
+
+                        return None;
+                    }
+                };
+
+                debug!("Processing entry {}", entry.id());
+
+                if entry.is_degraded() {
+                    degraded += 1;
+                }
+
+                count += 1;
+                newest = newest.max(Some(entry.published().timestamp()));
+
+                match entry_data(&entry) {
+                    Ok(data) => Some(Ok(data)),
+                    Err(err) => {
+                        failure = Some(err);
+
+                        Some(Err(DatabaseError::interrupted()))
+                    }
+                }
+            });
+
+            self.database.save_entry_stream(member, &mut data)
+        };
+
+        if let Some(err) = failure {
+            return Err(err);
+        }
+
+        let stats = result.map_err(WorkerError::database_error)?;
+
+        if let (Some(positions), Some(newest)) = (self.positions.as_ref(), newest) {
+            positions.advance(member, newest);
+        }
+
+        if degraded > 0 {
+            warn!(
+                "Feed of {} contains {} degraded entries of {}",
+                member, degraded, count
+            );
+        }
+
+        debug!(
+            "Saved {} new and {} updated entries of {} for {}, {} skipped",
+            stats.inserted(),
+            stats.updated(),
+            count,
+            member,
+            skipped
+        );
+
+        Ok(())
+    }
+}
+
+/// Serializes entry for storage.
+pub fn entry_data(entry: &Entry) -> WorkerResult<EntryData> {
+    let published = entry.published().timestamp();
+    let updated = entry.updated().timestamp();
+    let data = serde_yaml::to_string(entry).map_err(WorkerError::serialization_error)?;
+
+    Ok(EntryData::new(entry.id(), published, updated, &data))
+}